@@ -1,3 +1,8 @@
+use crate::advent::intcode;
+use crate::advent::intcode::symbolic::Goal;
+use crate::advent::intcode::symbolic::Solver;
+use crate::advent::intcode::Program;
+
 pub fn step1() -> usize {
     let mut input = parse_input();
     input[1] = 12;
@@ -6,19 +11,15 @@ pub fn step1() -> usize {
 }
 
 pub fn step2(expected_result: usize) -> Option<usize> {
-    let mut input = parse_input();
-
-    for noun in 0..=99 {
-        for verb in 0..=99 {
-            input[1] = noun;
-            input[2] = verb;
-            let result = execute_program(input.clone())[0];
-            if result == expected_result {
-                return Some(100 * noun + verb);
-            }
-        }
-    }
-    None
+    let program = Program::new(intcode::parse_input("02"));
+    Solver::new(&program)
+        .unknown_memory(1, 0..=99)
+        .unknown_memory(2, 0..=99)
+        .solve(Goal::Memory {
+            address: 0,
+            value: expected_result as isize,
+        })
+        .map(|solution| 100 * solution[0] as usize + solution[1] as usize)
 }
 
 fn parse_input() -> Vec<usize> {
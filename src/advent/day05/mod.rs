@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::advent::intcode::transcript::Transcript;
     use crate::advent::intcode::*;
 
     #[test]
@@ -17,4 +18,10 @@ mod tests {
         program.execute();
         assert_eq!(program.output.pop_back(), Some(9168267));
     }
+
+    #[test]
+    fn replay_step1_transcript() {
+        let transcript = Transcript::load("src/advent/day05/step1-transcript.txt").unwrap();
+        assert_eq!(transcript.replay(Program::new(parse_input("05"))), Ok(()));
+    }
 }
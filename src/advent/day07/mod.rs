@@ -2,7 +2,7 @@ use crate::advent::intcode::Program;
 use crate::advent::intcode::ProgramState;
 use permutohedron::Heap;
 
-pub fn find_best_value(input: Vec<isize>, feedback: bool) -> isize {
+pub fn find_best_value(input: Vec<isize>, feedback: bool) -> Result<isize, ProgramState> {
     let mut result = 0;
     let mut settings_value = if feedback {
         vec![5isize, 6, 7, 8, 9]
@@ -11,12 +11,17 @@ pub fn find_best_value(input: Vec<isize>, feedback: bool) -> isize {
     };
     let settings = Heap::new(&mut settings_value);
     for setting in settings {
-        result = result.max(amplifier_controller(input.clone(), &setting, feedback));
+        result = result.max(amplifier_controller(input.clone(), &setting, feedback)?);
     }
-    result
+    Ok(result)
 }
 
-fn amplifier_controller(input: Vec<isize>, settings: &[isize], feedback: bool) -> isize {
+// Fails with the state of the first amplifier that stops without halting
+fn amplifier_controller(
+    input: Vec<isize>,
+    settings: &[isize],
+    feedback: bool,
+) -> Result<isize, ProgramState> {
     let mut amps = Vec::new();
     for &setting in settings {
         let mut amp = Program::new(input.clone());
@@ -25,6 +30,7 @@ fn amplifier_controller(input: Vec<isize>, settings: &[isize], feedback: bool) -
     }
     amps[0].input.push_back(0);
     amps[0].execute();
+    check_amp(&amps[0])?;
 
     let mut exited = 0;
     let mut idx = 1;
@@ -33,7 +39,7 @@ fn amplifier_controller(input: Vec<isize>, settings: &[isize], feedback: bool) -
     } else {
         idx != 0
     } {
-        if amps[idx].state != ProgramState::Halted {
+        if !amps[idx].state.is_finished() {
             while let Some(input) = amps[if idx == 0 {
                 settings.len() - 1
             } else {
@@ -45,24 +51,35 @@ fn amplifier_controller(input: Vec<isize>, settings: &[isize], feedback: bool) -
                 amps[idx].input.push_back(input);
             }
             amps[idx].execute();
-            if amps[idx].state == ProgramState::Halted {
+            check_amp(&amps[idx])?;
+            if amps[idx].state.is_finished() {
                 exited += 1;
             }
         }
         idx = (idx + 1) % settings.len();
     }
-    *amps
+    Ok(*amps
         .iter()
         .map(|amp| &amp.output)
         .find(|output| !output.is_empty())
         .map(|output| output.get(0).unwrap())
-        .unwrap()
+        .unwrap())
+}
+
+// The other amplifiers would wait forever for the outputs of a broken one
+fn check_amp(amp: &Program) -> Result<(), ProgramState> {
+    if amp.state.is_finished() && !amp.state.is_halted() {
+        Err(amp.state.clone())
+    } else {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::advent::intcode::parse_input;
+    use crate::advent::intcode::Fault;
 
     #[test]
     fn check_amplifier_1() {
@@ -72,7 +89,7 @@ mod tests {
                 &vec!(4, 3, 2, 1, 0),
                 false,
             ),
-            43210
+            Ok(43210)
         );
     }
 
@@ -87,7 +104,7 @@ mod tests {
                 &vec!(0, 1, 2, 3, 4),
                 false,
             ),
-            54321
+            Ok(54321)
         );
     }
 
@@ -102,7 +119,7 @@ mod tests {
                 &vec!(1, 0, 4, 3, 2),
                 false,
             ),
-            65210
+            Ok(65210)
         );
     }
 
@@ -113,7 +130,7 @@ mod tests {
                 vec!(3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0),
                 false,
             ),
-            43210
+            Ok(43210)
         );
     }
 
@@ -127,7 +144,7 @@ mod tests {
                 ),
                 false,
             ),
-            54321
+            Ok(54321)
         );
     }
 
@@ -141,13 +158,13 @@ mod tests {
                 ),
                 false,
             ),
-            65210
+            Ok(65210)
         );
     }
 
     #[test]
     fn check_step1() {
-        assert_eq!(find_best_value(parse_input("07"), false), 47064);
+        assert_eq!(find_best_value(parse_input("07"), false), Ok(47064));
     }
 
     #[test]
@@ -161,7 +178,7 @@ mod tests {
                 &vec!(9, 8, 7, 6, 5),
                 true,
             ),
-            139629729
+            Ok(139629729)
         );
     }
 
@@ -177,7 +194,7 @@ mod tests {
                 &vec!(9, 7, 8, 5, 6),
                 true,
             ),
-            18216
+            Ok(18216)
         );
     }
 
@@ -191,7 +208,7 @@ mod tests {
                 ),
                 true,
             ),
-            139629729
+            Ok(139629729)
         );
     }
 
@@ -206,12 +223,23 @@ mod tests {
                 ),
                 true,
             ),
-            18216
+            Ok(18216)
         );
     }
 
     #[test]
     fn check_step2() {
-        assert_eq!(find_best_value(parse_input("07"), true), 4248984);
+        assert_eq!(find_best_value(parse_input("07"), true), Ok(4248984));
+    }
+
+    #[test]
+    fn faulted_amplifier_stops_the_feedback_loop() {
+        assert_eq!(
+            amplifier_controller(vec!(3, 9, 3, 9, 42), &[5, 6, 7, 8, 9], true),
+            Err(ProgramState::Faulted {
+                address: 4,
+                fault: Fault::UnknownOpcode(42)
+            })
+        );
     }
 }
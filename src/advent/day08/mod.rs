@@ -1,4 +1,7 @@
+use crate::advent::geometry::export;
+use crate::advent::geometry::Map;
 use crate::read_file;
+use std::io;
 use std::iter::once;
 
 const BLACK: char = '0';
@@ -38,6 +41,17 @@ pub fn step2() -> String {
         .collect()
 }
 
+// The decoded image as a PBM picture
+pub fn picture() -> String {
+    let image: Map<char> = step2().parse().unwrap();
+    image.to_pbm(4, &|pixel| pixel == Some(&'#'))
+}
+
+// The decoded image, saved in the reports
+pub fn report() -> io::Result<()> {
+    export::save("day08-image.pbm", &picture())
+}
+
 fn parse_input() -> Vec<char> {
     read_file("src/advent/day08/input.txt").chars().collect()
 }
@@ -61,4 +75,9 @@ mod tests {
     fn check_step2() {
         assert_eq!(step2(), STEP2);
     }
+
+    #[test]
+    fn check_picture() {
+        assert!(picture().starts_with("P1\n100 24\n1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 0"));
+    }
 }
@@ -1,3 +1,4 @@
+use crate::advent::geometry::lattice;
 use crate::advent::geometry::Point;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -24,49 +25,56 @@ pub fn find_best_asteroid(asteroids: &[Point]) -> Option<(Point, usize)> {
     let mut result = None;
     let mut count = 0;
     for &a in asteroids {
-        let mut angles = HashSet::new();
-        for &b in asteroids {
-            if a != b {
-                // multiplication by 100_000 and conversion to isize because Eq is not implemented f64
-                angles.insert((a.angle_with(&b) * 100_000.0) as isize);
-            }
-        }
-        if angles.len() > count {
-            count = angles.len();
+        let directions = asteroids
+            .iter()
+            .filter(|&&b| a != b)
+            .map(|b| lattice::direction(&a, b))
+            .collect::<HashSet<Point>>();
+        if directions.len() > count {
+            count = directions.len();
             result = Some((a, count));
         }
     }
     result
 }
 
+// nth starts at 1, every asteroid but the station is vaporized at some point
 pub fn find_nth_vaporized(asteroids: &[Point], nth: usize) -> Point {
+    assert!(
+        nth >= 1 && nth < asteroids.len(),
+        "only {} asteroids can be vaporized",
+        asteroids.len().saturating_sub(1)
+    );
     let (station, _) = find_best_asteroid(asteroids).unwrap();
 
-    let mut angles: HashMap<isize, Point> = HashMap::new();
+    // asteroids on each line of sight, the closest last
+    let mut lines: HashMap<Point, Vec<Point>> = HashMap::new();
     for &b in asteroids {
         if station != b {
-            let angle = -(station.angle_with(&b) * 100_000.0) as isize;
-            angles
-                .entry(angle)
-                .and_modify(|other| {
-                    if other.manhattan_distance_from(&station) > b.manhattan_distance_from(&station)
-                    {
-                        *other = b;
-                    }
-                })
-                .or_insert(b);
+            lines
+                .entry(lattice::direction(&station, &b))
+                .or_default()
+                .push(b);
         }
     }
+    for line in lines.values_mut() {
+        line.sort_by_key(|b| std::cmp::Reverse(b.manhattan_distance_from(&station)));
+    }
+    let mut directions = lines.keys().cloned().collect::<Vec<Point>>();
+    directions.sort_by(lattice::clockwise_cmp);
 
-    let mut keys = angles.keys().collect::<Vec<&isize>>();
-    keys.sort();
-    let angle = keys
-        .iter()
-        .cycle()
-        .skip_while(|&&&a| a < 0)
-        .nth(nth - 1)
-        .unwrap();
-    *angles.get(angle).unwrap()
+    // each turn of the laser vaporizes the closest asteroid of every line
+    let mut vaporized = 0;
+    loop {
+        for direction in &directions {
+            if let Some(asteroid) = lines.get_mut(direction).unwrap().pop() {
+                vaporized += 1;
+                if vaporized == nth {
+                    return asteroid;
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -89,4 +97,10 @@ mod tests {
             Point { x: 5, y: 2 }
         );
     }
+
+    #[test]
+    #[should_panic(expected = "asteroids can be vaporized")]
+    fn vaporize_past_the_last_asteroid_should_panic() {
+        find_nth_vaporized(&parse_map(String::from(".#.\n###")), 4);
+    }
 }
@@ -1,13 +1,16 @@
+use std::io;
+
+use crate::advent::geometry::direction::Direction;
+use crate::advent::geometry::export;
+use crate::advent::geometry::export::Colour;
 use crate::advent::geometry::Map;
 use crate::advent::geometry::ORIGIN;
-use crate::advent::geometry::UP;
 use crate::advent::intcode::parse_input;
 use crate::advent::intcode::Program;
-use crate::advent::intcode::ProgramState;
 
 pub fn step1() -> usize {
     let map = run_robot(false);
-    map.values.len()
+    map.values().len()
 }
 
 pub fn step2() -> String {
@@ -15,6 +18,20 @@ pub fn step2() -> String {
     format!("{}", &map)
 }
 
+// The painted hull, saved in the reports
+pub fn report() -> io::Result<()> {
+    let map = run_robot(true);
+    export::save("day11-hull.ppm", &map.to_ppm(8, &panel_colour))?;
+    export::save("day11-hull.svg", &map.to_svg(8, &panel_colour))
+}
+
+fn panel_colour(panel: Option<&bool>) -> Colour {
+    match panel {
+        Some(true) => Colour::WHITE,
+        _ => Colour::BLACK,
+    }
+}
+
 fn run_robot(first_value: bool) -> Map<bool> {
     let mut map = Map::new(
         |f, value| {
@@ -31,21 +48,25 @@ fn run_robot(first_value: bool) -> Map<bool> {
     );
     let mut prog = Program::new(parse_input("11"));
     let mut current_point = ORIGIN;
-    let mut current_direction = UP;
-    map.values.insert(current_point, first_value);
+    let mut current_direction = Direction::North;
+    map.insert(current_point, first_value);
 
-    while prog.state != ProgramState::Halted {
-        let input = map.values.get(&current_point).unwrap_or(&false);
-        prog.input.push_back(*input as isize);
-        prog.execute();
-        let output = prog.output.pop_front().expect("must have a color output");
-        let turn = prog
-            .output
-            .pop_front()
-            .expect("must have a direction output");
-        current_direction = current_direction.rotate(turn == 1);
-        map.values.insert(current_point, output != 0);
-        current_point += current_direction;
+    while !prog.state.is_finished() {
+        let input = map.values().get(&current_point).unwrap_or(&false);
+        let (output, turn) = match prog.exchange(vec![*input as isize])[..] {
+            [output, turn] => (output, turn),
+            _ => panic!(
+                "must have a color and a direction outputs, robot {}",
+                prog.state
+            ),
+        };
+        current_direction = if turn == 1 {
+            current_direction.turn_right()
+        } else {
+            current_direction.turn_left()
+        };
+        map.insert(current_point, output != 0);
+        current_point += current_direction.to_point();
     }
     map
 }
@@ -71,4 +92,15 @@ mod tests {
     fn check_step2() {
         assert_eq!(step2(), STEP2.to_string());
     }
+
+    #[test]
+    fn check_pictures() {
+        let map = run_robot(true);
+        assert!(map
+            .to_ppm(8, &panel_colour)
+            .starts_with("P3\n344 48\n255\n0 0 0"));
+        assert!(map.to_svg(8, &panel_colour).starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"344\" height=\"48\">\n"
+        ));
+    }
 }
@@ -13,11 +13,11 @@ impl Moon {
         self.position += self.velocity;
     }
     fn potential_energy(&self) -> usize {
-        (self.position.x.abs() + self.position.y.abs() + self.position.z.abs()) as usize
+        self.position.manhattan_norm()
     }
 
     fn kinetic_energy(&self) -> usize {
-        (self.velocity.x.abs() + self.velocity.y.abs() + self.velocity.z.abs()) as usize
+        self.velocity.manhattan_norm()
     }
     fn has_common_axis(&self, other: &Self) -> [bool; 3] {
         [
@@ -93,25 +93,13 @@ pub fn step2(input: &[D3Point; 4]) -> usize {
 fn apply_gravity(moons: &mut Vec<Moon>) {
     for i in 0..4 {
         for j in i + 1..4 {
-            let velocity_change = moons[i]
-                .position
-                .compute_velocity_change(&moons[j].position);
+            let velocity_change = (moons[j].position - moons[i].position).signum();
             moons[i].velocity += velocity_change;
             moons[j].velocity -= velocity_change;
         }
     }
 }
 
-impl D3Point {
-    fn compute_velocity_change(&self, other: &D3Point) -> Self {
-        D3Point {
-            x: other.x.cmp(&self.x) as isize,
-            y: other.y.cmp(&self.y) as isize,
-            z: other.z.cmp(&self.z) as isize,
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -9,9 +9,13 @@ use termion::{color, cursor, style};
 
 const SCORE_FLAG: Point = Point { x: -1, y: 0 };
 
-pub fn execute(mut input: Vec<isize>, display: bool) -> (usize, isize) {
-    input[0] = 2; // 2 quarters
-    let mut program = Program::new(input);
+pub fn execute(input: Vec<isize>, display: bool) -> Result<(usize, isize), ProgramState> {
+    let mut program = Program::with_patches(input, &[(0, 2)]); // 2 quarters
+    play(&mut program, display)
+}
+
+// Fails with the state of a game that stops without halting
+pub fn play(program: &mut Program, display: bool) -> Result<(usize, isize), ProgramState> {
     let mut map = Map::new(cell_formatter, newline_formatter);
     let mut ball = ORIGIN;
     let mut paddle = ORIGIN;
@@ -20,18 +24,14 @@ pub fn execute(mut input: Vec<isize>, display: bool) -> (usize, isize) {
 
     let mut first_display = true;
 
-    while program.state != ProgramState::Halted {
-        program.execute();
-        while program.output.len() > 2 {
-            let point = Point::new(
-                program.output.pop_front().unwrap(),
-                program.output.pop_front().unwrap(),
-            );
-            let value = program.output.pop_front().unwrap();
+    while !program.state.is_finished() {
+        for tile in program.chunks(3) {
+            let point = Point::new(tile[0], tile[1]);
+            let value = tile[2];
             if point == SCORE_FLAG {
                 score = value;
             } else {
-                map.values.insert(point, value);
+                map.insert(point, value);
                 if value == 4 {
                     ball = point;
                 } else if value == 3 {
@@ -40,13 +40,16 @@ pub fn execute(mut input: Vec<isize>, display: bool) -> (usize, isize) {
             }
         }
         if nb_blocks.is_none() {
-            nb_blocks = Some(map.values.values().filter(|&&cell| cell == 2).count());
+            nb_blocks = Some(map.values().values().filter(|&&cell| cell == 2).count());
         }
         program.input.push_back(ball.x.cmp(&paddle.x) as isize);
         first_display = display_game(display, &map, score, first_display);
     }
     print!("{}", cursor::Show);
-    (nb_blocks.unwrap(), score)
+    if !program.state.is_halted() {
+        return Err(program.state.clone());
+    }
+    Ok((nb_blocks.unwrap(), score))
 }
 
 // Disable display coverage to avoid long tests
@@ -128,15 +131,54 @@ fn newline_formatter(f: &mut Formatter<'_>, width: usize) -> Result<(), Error> {
 mod tests {
     use super::*;
     use crate::advent::intcode::parse_input;
+    use crate::advent::intcode::watch::diff_programs;
+    use crate::advent::intcode::Fault;
 
     #[test]
     fn test_with_input() {
-        assert_eq!(execute(parse_input("13"), false), (253, 12263));
+        assert_eq!(execute(parse_input("13"), false), Ok((253, 12263)));
+    }
+
+    #[test]
+    fn locate_score_cell() {
+        let mut program = Program::with_patches(parse_input("13"), &[(0, 2)]);
+        let before = program.clone();
+        let (_, score) = play(&mut program, false).unwrap();
+        let score_cells: Vec<usize> = diff_programs(&before, &program)
+            .iter()
+            .flat_map(|region| region.changes.iter())
+            .filter(|change| change.new == score)
+            .map(|change| change.address)
+            .collect();
+        assert_eq!(score_cells, vec![386]);
+    }
+
+    #[test]
+    fn replay_recorded_game_session() {
+        let mut program = Program::with_patches(parse_input("13"), &[(0, 2)]);
+        program.record_transcript();
+        play(&mut program, false).unwrap();
+        let transcript = program.transcript.unwrap();
+        assert_eq!(
+            transcript.replay(Program::with_patches(parse_input("13"), &[(0, 2)])),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn broken_game_reports_its_fault() {
+        assert_eq!(
+            execute(vec![0, 0, 0, 0, 104, 1, 104, 2, 104, 3, 42], false),
+            Err(ProgramState::Faulted {
+                address: 10,
+                fault: Fault::UnknownOpcode(42)
+            })
+        );
     }
 
     #[test]
     #[ignore]
     fn test_with_input_debug() {
-        assert_eq!(execute(parse_input("13"), true), (253, 12263));
+        assert_eq!(execute(parse_input("13"), true), Ok((253, 12263)));
     }
 }
@@ -1,3 +1,5 @@
+use crate::advent::geometry::export;
+use crate::advent::geometry::export::Colour;
 use crate::advent::geometry::Map;
 use crate::advent::geometry::Point;
 use crate::advent::geometry::DOWN;
@@ -7,38 +9,34 @@ use crate::advent::geometry::RIGHT;
 use crate::advent::geometry::UP;
 use crate::advent::intcode::Program;
 use std::collections::vec_deque::VecDeque;
+use std::io;
 
 #[derive(Clone)]
 pub struct State {
     position: Point,
-    step: usize,
     program: Program,
 }
 
-#[allow(clippy::map_entry)]
-pub fn execute(input: Program) -> Result<(usize, Option<Program>), &'static str> {
+// Walls, open cells and the oxygen system, as seen by the droid visiting every reachable cell
+pub fn explore(input: Program) -> Result<Map<char>, &'static str> {
     let mut visited = Map::new(
         |f, v| write!(f, "{}", v.unwrap_or(&'?')),
         |f, _| writeln!(f),
     );
     let mut queue = VecDeque::new();
-    let mut max = 0;
 
     queue.push_back(State {
         position: ORIGIN,
-        step: 0,
         program: input,
     });
-    visited.values.insert(ORIGIN, '+');
+    visited.insert(ORIGIN, '+');
 
     while let Some(state) = queue.pop_front() {
-        max = max.max(state.step);
         for (direction, direction_value) in &[(UP, 1), (RIGHT, 4), (DOWN, 2), (LEFT, 3)] {
             let new_position = state.position + *direction;
-            if !visited.values.contains_key(&new_position) {
+            if !visited.values().contains_key(&new_position) {
                 let mut new_state = State {
                     position: new_position,
-                    step: state.step + 1,
                     program: state.program.clone(),
                 };
                 new_state.program.input.push_back(*direction_value);
@@ -46,18 +44,17 @@ pub fn execute(input: Program) -> Result<(usize, Option<Program>), &'static str>
                 match new_state.program.output.pop_front() {
                     Some(0) => {
                         // hit a wall
-                        visited.values.insert(new_position, '#');
+                        visited.insert(new_position, '#');
                     }
                     Some(1) => {
                         // move to direction
                         queue.push_back(new_state);
-                        visited.values.insert(new_position, '.');
+                        visited.insert(new_position, '.');
                     }
                     Some(2) => {
                         // found oxygen
-                        visited.values.insert(new_position, 'O');
-                        println!("{}", &visited);
-                        return Ok((new_state.step, Some(new_state.program)));
+                        queue.push_back(new_state);
+                        visited.insert(new_position, 'O');
                     }
                     _ => {
                         return Err("program should always return a valid output");
@@ -66,8 +63,39 @@ pub fn execute(input: Program) -> Result<(usize, Option<Program>), &'static str>
             }
         }
     }
-    println!("{}", &visited);
-    Ok((max, None))
+    Ok(visited)
+}
+
+// The explored maze, saved in the reports
+pub fn report(map: &Map<char>) -> io::Result<()> {
+    export::save("day15-maze.ppm", &map.to_ppm(4, &cell_colour))?;
+    export::save("day15-maze.svg", &map.to_svg(4, &cell_colour))
+}
+
+fn cell_colour(cell: Option<&char>) -> Colour {
+    match cell {
+        Some('#') => Colour::grey(64),
+        Some('.') => Colour::WHITE,
+        Some('O') => Colour::new(0, 128, 255),
+        Some('+') => Colour::new(255, 0, 0),
+        _ => Colour::BLACK,
+    }
+}
+
+fn is_open(value: &char) -> bool {
+    *value != '#'
+}
+
+// Fewest moves from the start to the oxygen system
+pub fn step1(map: &Map<char>) -> Option<usize> {
+    let oxygen = map.find('O')?;
+    map.distance_field(ORIGIN, &is_open).get(&oxygen).cloned()
+}
+
+// Minutes for the oxygen to fill the area : the farthest cell from the oxygen system
+pub fn step2(map: &Map<char>) -> Option<usize> {
+    let oxygen = map.find('O')?;
+    map.distance_field(oxygen, &is_open).values().max().cloned()
 }
 
 #[cfg(test)]
@@ -77,12 +105,25 @@ mod tests {
 
     #[test]
     fn check_step1() {
-        assert_eq!(execute(Program::new(parse_input("15"))).unwrap().0, 214);
+        let map = explore(Program::new(parse_input("15"))).unwrap();
+        assert_eq!(step1(&map), Some(214));
     }
 
     #[test]
     fn check_step2() {
-        let program_step1 = execute(Program::new(parse_input("15"))).unwrap().1.unwrap();
-        assert_eq!(execute(program_step1).unwrap().0, 344);
+        let map = explore(Program::new(parse_input("15"))).unwrap();
+        assert_eq!(step2(&map), Some(344));
+    }
+
+    #[test]
+    fn check_pictures() {
+        let map = explore(Program::new(parse_input("15"))).unwrap();
+        assert!(map
+            .to_ppm(4, &cell_colour)
+            .starts_with("P3\n164 164\n255\n0 0 0"));
+        let svg = map.to_svg(4, &cell_colour);
+        assert!(svg.contains("<rect x=\"4\" y=\"0\" width=\"60\" height=\"4\" fill=\"#404040\"/>"));
+        // a single oxygen system
+        assert_eq!(svg.matches("#0080ff").count(), 1);
     }
 }
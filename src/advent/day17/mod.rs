@@ -2,33 +2,28 @@ use std::collections::HashSet;
 
 use regex::Regex;
 
+use crate::advent::geometry::direction::Direction;
 use crate::advent::geometry::Map;
-use crate::advent::geometry::Point;
-use crate::advent::geometry::DOWN;
-use crate::advent::geometry::LEFT;
-use crate::advent::geometry::RIGHT;
-use crate::advent::geometry::UP;
 use crate::advent::intcode::Program;
 
 pub fn execute(input: Vec<isize>) -> Result<(isize, isize), &'static str> {
     let mut map = build_map(input.clone());
 
     let step1 = map
-        .values
+        .values()
         .iter()
         .filter(|(_, &v)| v == '#')
         .filter(|(&point, _)| {
-            [UP, RIGHT, DOWN, LEFT]
+            point
+                .neighbours4()
                 .iter()
-                .all(|&dir| *map.values.get(&(point + dir)).unwrap_or(&'?') == '#')
+                .all(|neighbour| *map.values().get(neighbour).unwrap_or(&'?') == '#')
         })
         .map(|(&p, _)| p.x * p.y)
         .sum();
 
     // Execute step 2
-    let mut input = input;
-    input[0] = 2;
-    let mut program = Program::new(input);
+    let mut program = Program::with_patches(input, &[(0, 2)]);
 
     let instructions = compute_instructions(&mut map);
     let (main_routine, functions) = extract_patterns(&instructions.join(""));
@@ -51,66 +46,62 @@ pub fn execute(input: Vec<isize>) -> Result<(isize, isize), &'static str> {
 fn build_map(input: Vec<isize>) -> Map<char> {
     let mut program = Program::new(input);
     program.execute();
-    let mut map = Map::new(
-        |f, v| write!(f, "{}", v.unwrap_or(&'?')),
-        |f, _| writeln!(f),
-    );
-    let mut y = 0;
-    let mut x = 0;
-    while let Some(item) = program.output.pop_front() {
-        if item == 10 {
-            y += 1;
-            x = 0;
-        } else {
-            map.values.insert(Point::new(x, y), item as u8 as char);
-            x += 1;
-        }
-    }
-    map
+    program
+        .output
+        .iter()
+        .map(|&item| item as u8 as char)
+        .collect::<String>()
+        .parse()
+        .unwrap()
 }
 
 fn compute_instructions(map: &mut Map<char>) -> Vec<String> {
     let mut instructions = Vec::new();
 
     let mut robot = *map
-        .values
+        .values()
         .iter()
         .find(|(_, &value)| value == '^')
         .unwrap()
         .0;
-    let mut direction = UP;
+    let mut direction = Direction::North;
 
     let mut visited = HashSet::new();
     let mut current_instruction = 0;
-    let nb_part = map.values.values().filter(|&v| *v == '#').count();
+    let nb_part = map.values().values().filter(|&v| *v == '#').count();
     while nb_part > visited.len() {
-        if *map.values.get(&(robot + direction)).unwrap_or(&'?') != '#' {
+        if *map
+            .values()
+            .get(&(robot + direction.to_point()))
+            .unwrap_or(&'?')
+            != '#'
+        {
             if current_instruction != 0 {
                 instructions.push(current_instruction.to_string());
             }
             current_instruction = 0;
             if *map
-                .values
-                .get(&(robot + direction.rotate(true)))
+                .values()
+                .get(&(robot + direction.turn_right().to_point()))
                 .unwrap_or(&'?')
                 == '#'
             {
-                direction = direction.rotate(true);
+                direction = direction.turn_right();
                 instructions.push("R".to_string());
             } else if *map
-                .values
-                .get(&(robot + direction.rotate(false)))
+                .values()
+                .get(&(robot + direction.turn_left().to_point()))
                 .unwrap_or(&'?')
                 == '#'
             {
-                direction = direction.rotate(false);
+                direction = direction.turn_left();
                 instructions.push("L".to_string());
             } else {
                 panic!("No path")
             }
         }
         current_instruction += 1;
-        robot += direction;
+        robot += direction.to_point();
         visited.insert(robot);
     }
     instructions.push(current_instruction.to_string());
@@ -1,14 +1,9 @@
-use std::collections::vec_deque::VecDeque;
-use std::collections::HashMap;
 use std::hash::Hash;
 
+use crate::advent::geometry::rect::Rect;
 use crate::advent::geometry::Map;
 use crate::advent::geometry::Point;
-use crate::advent::geometry::DOWN;
-use crate::advent::geometry::LEFT;
-use crate::advent::geometry::RIGHT;
-use crate::advent::geometry::UP;
-use crate::read_file;
+use crate::advent::search;
 
 #[derive(Eq, PartialEq, Hash, Clone)]
 struct State {
@@ -24,11 +19,10 @@ fn is_door(value: char) -> bool {
     value.is_alphabetic() && value.is_uppercase()
 }
 
-#[allow(clippy::map_entry)]
 pub fn step1(map: Map<char>) -> Option<usize> {
     let start = State {
         position: map
-            .values
+            .values()
             .iter()
             .find(|&(_, &v)| v == '@')
             .map(|(&p, _)| p)
@@ -36,29 +30,23 @@ pub fn step1(map: Map<char>) -> Option<usize> {
         keys: vec![],
     };
     let keys = map
-        .values
+        .values()
         .iter()
         .filter(|&(_, &v)| is_key(v))
         .map(|(_, &v)| v)
         .collect::<Vec<char>>();
-    let mut paths = HashMap::new();
-    paths.insert(start.clone(), 0usize);
 
-    let mut queue = VecDeque::new();
-    queue.push_back(start);
-
-    while let Some(current) = queue.pop_front() {
-        for &dir in &[UP, LEFT, DOWN, RIGHT] {
-            let next_position = current.position + dir;
-            let next_value = *map.values.get(&next_position).unwrap_or(&'#');
-            let mut next = None;
+    let successors = |current: &State| {
+        let mut nexts = vec![];
+        for &next_position in &current.position.neighbours4() {
+            let next_value = *map.values().get(&next_position).unwrap_or(&'#');
 
             if is_door(next_value) {
                 if !keys.contains(&next_value.to_ascii_lowercase())
                     || current.keys.contains(&next_value.to_ascii_lowercase())
                 {
                     // door open
-                    next = Some(State {
+                    nexts.push(State {
                         position: next_position,
                         keys: current.keys.clone(),
                     });
@@ -68,57 +56,52 @@ pub fn step1(map: Map<char>) -> Option<usize> {
                 if !current.keys.contains(&next_value) {
                     next_keys.push(next_value);
                     next_keys.sort();
-                    if next_keys.len() == keys.len() {
-                        return Some(paths.get(&current).unwrap() + 1);
-                    }
                 }
-                next = Some(State {
+                nexts.push(State {
                     position: next_position,
                     keys: next_keys,
                 });
             } else if next_value != '#' {
-                next = Some(State {
+                nexts.push(State {
                     position: next_position,
                     keys: current.keys.clone(),
                 });
             }
-
-            if let Some(next_state) = next {
-                if !paths.contains_key(&next_state) {
-                    queue.push_back(next_state.clone());
-                    paths.insert(next_state, paths.get(&current).unwrap() + 1);
-                }
-            }
         }
-    }
-    None
+        nexts
+    };
+    search::bfs(start, &successors, &|state| state.keys.len() == keys.len()).goal_distance()
 }
 
-pub fn step2() -> usize {
-    step1(parse_input(read_file("src/advent/day18/input-step2-a.txt"))).unwrap()
-        + step1(parse_input(read_file("src/advent/day18/input-step2-b.txt"))).unwrap()
-        + step1(parse_input(read_file("src/advent/day18/input-step2-c.txt"))).unwrap()
-        + step1(parse_input(read_file("src/advent/day18/input-step2-d.txt"))).unwrap()
+// The entrance is walled off into 4 vaults, one robot each. Doors whose key is in another
+// vault are considered open : their robot will get it
+pub fn step2(map: Map<char>) -> usize {
+    let center = map.find('@').expect("should have a start position");
+    let mut map = map;
+    map.blit(
+        &parse_input("@#@\n###\n@#@".to_string()),
+        center + Point::new(-1, -1),
+    );
+    let bounds = map.bounds();
+    [
+        bounds.top_left,
+        Point::new(bounds.bottom_right.x, bounds.top_left.y),
+        Point::new(bounds.top_left.x, bounds.bottom_right.y),
+        bounds.bottom_right,
+    ]
+    .iter()
+    .map(|&corner| step1(map.crop(&Rect::new(center, corner))).unwrap())
+    .sum()
 }
 
 fn parse_input(input: String) -> Map<char> {
-    let mut result = Map::new(
-        |f, v| write!(f, "{}", v.unwrap_or(&'?')),
-        |f, _| writeln!(f),
-    );
-    for (y, line) in input.lines().enumerate() {
-        for (x, value) in line.chars().enumerate() {
-            result
-                .values
-                .insert(Point::new(x as isize, y as isize), value);
-        }
-    }
-    result
+    input.parse().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::read_file;
 
     #[test]
     fn check_example1_step1() {
@@ -157,8 +140,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_example_step2() {
+        assert_eq!(
+            step2(parse_input(
+                r#"#######
+#a.#Cd#
+##...##
+##.@.##
+##...##
+#cB#Ab#
+#######"#
+                    .to_string()
+            )),
+            8
+        );
+    }
+
     #[test]
     fn check_step2() {
-        assert_eq!(step2(), 1844);
+        assert_eq!(
+            step2(parse_input(read_file("src/advent/day18/input.txt"))),
+            1844
+        );
     }
 }
@@ -23,17 +23,17 @@ pub fn step1(input: Vec<isize>, offset: Point, size: isize) -> usize {
             program.input.push_back(x as isize);
             program.input.push_back(y as isize);
             program.execute();
-            map.values
-                .insert(Point::new(x, y), program.output.pop_front().unwrap() == 1);
+            map.insert(Point::new(x, y), program.output.pop_front().unwrap() == 1);
         }
     }
     println!("{}", &map);
-    map.values.iter().filter(|&(_, &v)| v).count()
+    map.values().iter().filter(|&(_, &v)| v).count()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::advent::geometry::ORIGIN;
+    use crate::advent::intcode::coverage::Coverage;
     use crate::advent::intcode::parse_input;
 
     use super::*;
@@ -43,6 +43,27 @@ mod tests {
         assert_eq!(step1(parse_input("19"), ORIGIN, 50), 215);
     }
 
+    #[test]
+    fn beam_queries_coverage() {
+        let image = parse_input("19");
+        let program = Program::new(image.clone());
+        let mut merged = Coverage::default();
+        let mut smallest = 100.;
+        for (x, y) in (0..10).flat_map(|x| (0..10).map(move |y| (x, y))) {
+            let mut query = program.clone();
+            query.record_coverage();
+            query.exchange(vec![x, y]);
+            let coverage = query.coverage.unwrap();
+            smallest = coverage.ratio(image.len()).min(smallest);
+            merged.merge(&coverage);
+        }
+        // every query starts at address 0
+        assert_eq!(merged.executed[&0], 100);
+        // inside or outside the beam, the queries run the same instructions
+        assert_eq!(merged.ratio(image.len()), smallest);
+        assert!(smallest < 100.);
+    }
+
     #[test]
     #[ignore]
     fn check_step2() {
@@ -1,55 +1,35 @@
-use std::collections::vec_deque::VecDeque;
 use std::collections::HashMap;
 
+use crate::advent::geometry::grid::Grid;
 use crate::advent::geometry::Map;
+use crate::advent::geometry::Plane;
 use crate::advent::geometry::Point;
 use crate::advent::geometry::DOWN;
 use crate::advent::geometry::LEFT;
 use crate::advent::geometry::RIGHT;
 use crate::advent::geometry::UP;
+use crate::advent::search;
 
-#[allow(clippy::map_entry)]
 pub fn step1(input: String) -> Option<usize> {
-    let input = parse_input(input);
-    let raw_map = parse_raw_map(input);
-    let map = parse_map(&raw_map);
+    let map = Grid::from(&parse_map(&input.parse().unwrap()));
+    let portals = portals(&map);
 
-    let start = map.find("AA".to_string()).unwrap();
-    let mut queue = VecDeque::new();
-    let mut visited = HashMap::new();
-
-    queue.push_back(start);
-    visited.insert(start, 0usize);
-
-    while let Some(current) = queue.pop_front() {
-        for dir in &[UP, RIGHT, DOWN, LEFT] {
-            let neightboor = current + *dir;
-            if let Some(neightboor_value) = map.values.get(&neightboor) {
-                if neightboor_value != "#" && !visited.contains_key(&neightboor) {
-                    visited.insert(neightboor, visited.get(&current).unwrap_or(&0) + 1);
-                    if neightboor_value == "." {
-                        queue.push_back(neightboor);
-                    } else if neightboor_value == "ZZ" {
-                        return Some(*visited.get(&current).unwrap() - 1);
-                    } else {
-                        let other_gate_position = map
-                            .values
-                            .iter()
-                            .find(|(pos, val)| *val == neightboor_value && neightboor != **pos)
-                            .map(|(&pos, _)| pos)
-                            .unwrap();
-                        if !visited.contains_key(&other_gate_position) {
-                            queue.push_back(other_gate_position);
-                            visited
-                                .insert(other_gate_position, *visited.get(&current).unwrap_or(&0));
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    None
+    let successors = |position: &Point| {
+        position
+            .neighbours4()
+            .iter()
+            .filter_map(|next| match map[*next].as_str() {
+                "." => Some(*next),
+                "#" | "AA" | "ZZ" => None,
+                _ => Some(portals[next]),
+            })
+            .collect()
+    };
+    let end = entrance(&map, "ZZ");
+    search::bfs(entrance(&map, "AA"), &successors, &|&position| {
+        position == end
+    })
+    .goal_distance()
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
@@ -59,91 +39,96 @@ struct State {
 }
 
 #[cfg_attr(tarpaulin, skip)]
-#[allow(clippy::map_entry)]
 pub fn step2(input: String) -> Option<usize> {
-    let input = parse_input(input);
-    let raw_map = parse_raw_map(input);
-    let map = parse_map(&raw_map);
-
-    let start = State {
-        position: map.find("AA".to_string()).unwrap(),
-        level: 0,
-    };
-    let mut queue = VecDeque::new();
-    let mut visited = HashMap::new();
+    let map = Grid::from(&parse_map(&input.parse().unwrap()));
+    let portals = portals(&map);
+    let (_, bottom_right) = map.corners();
 
-    queue.push_back(start);
-    visited.insert(start, 0usize);
-    while let Some(current) = queue.pop_front() {
-        for dir in &[UP, RIGHT, DOWN, LEFT] {
-            let neightboor_pos = current.position + *dir;
-            if let Some(neightboor_value) = map.values.get(&neightboor_pos) {
-                if neightboor_value == "ZZ" && current.level == 0 {
-                    // end loop
-                    return Some(*visited.get(&current).unwrap() - 1);
-                }
-                if neightboor_value == "#" || neightboor_value == "AA" || neightboor_value == "ZZ" {
-                    // Stop
-                    continue;
-                }
-                // Add neightboor to bfs queue
-                let neightboor_state = State {
-                    position: neightboor_pos,
+    let successors = |current: &State| {
+        current
+            .position
+            .neighbours4()
+            .iter()
+            .filter_map(|next| match map[*next].as_str() {
+                "." => Some(State {
+                    position: *next,
                     level: current.level,
-                };
-                if !visited.contains_key(&neightboor_state) {
-                    visited.insert(neightboor_state, visited.get(&current).unwrap_or(&0) + 1);
-                    queue.push_back(neightboor_state);
-                }
-                // try to pass a gate
-                if neightboor_value != "." {
-                    let other_gate = map
-                        .find_with(&|pos, val| val == neightboor_value && neightboor_pos != *pos)
-                        .unwrap();
-                    let next_level = if neightboor_pos.x < 5
-                        || neightboor_pos.y < 5
-                        || neightboor_pos.x > map.width() as isize - 5
-                        || neightboor_pos.y > map.height() as isize - 5
+                }),
+                "#" | "AA" | "ZZ" => None,
+                _ => {
+                    // outer gates go up a level, inner gates go down
+                    let level = if next.x < 5
+                        || next.y < 5
+                        || next.x > bottom_right.x - 5
+                        || next.y > bottom_right.y - 5
                     {
                         current.level - 1
                     } else {
                         current.level + 1
                     };
-                    if next_level >= 0 {
-                        let next_gate_state = State {
-                            position: other_gate,
-                            level: next_level,
-                        };
-                        if !visited.contains_key(&next_gate_state) {
-                            visited.insert(next_gate_state, *visited.get(&current).unwrap_or(&0));
-                            queue.push_back(next_gate_state);
-                        }
+                    if level >= 0 {
+                        Some(State {
+                            position: portals[next],
+                            level,
+                        })
+                    } else {
+                        None
                     }
                 }
-            }
-        }
-    }
+            })
+            .collect()
+    };
+    let end = State {
+        position: entrance(&map, "ZZ"),
+        level: 0,
+    };
+    let start = State {
+        position: entrance(&map, "AA"),
+        level: 0,
+    };
+    search::bfs(start, &successors, &|&state| state == end).goal_distance()
+}
 
-    None
+// The open cell next to a gate
+fn entrance(map: &Grid<String>, gate: &str) -> Point {
+    let gate = map.find_with(&|_, v| v == gate).unwrap();
+    map.neighbours(&gate)
+        .iter()
+        .find(|(_, v)| v.as_str() == ".")
+        .map(|&(p, _)| p)
+        .unwrap()
 }
 
-fn parse_input(input: String) -> Vec<Vec<char>> {
-    input
-        .lines()
-        .map(|l| l.chars().collect::<Vec<char>>())
-        .collect()
+// Each gate leads to the entrance of its twin
+fn portals(map: &Grid<String>) -> HashMap<Point, Point> {
+    let mut portals = HashMap::new();
+    for gate in map.points() {
+        let name = &map[gate];
+        if name.len() == 2 && name != "AA" && name != "ZZ" {
+            let twin = map.find_with(&|p, v| v == name && *p != gate).unwrap();
+            let entrance = map
+                .neighbours(&twin)
+                .iter()
+                .find(|(_, v)| v.as_str() == ".")
+                .map(|&(p, _)| p)
+                .unwrap();
+            portals.insert(gate, entrance);
+        }
+    }
+    portals
 }
 
+// Missing cells are walls
 fn parse_map(raw_map: &Map<char>) -> Map<String> {
-    let mut map = Map::with_default_formatters(" ".to_string());
-    for (&position, value) in &raw_map.values {
+    let mut map = Map::with_default_formatters("#".to_string());
+    for (&position, value) in raw_map.values() {
         if *value == '.' || *value == '#' {
-            map.values.insert(position, format!("{}", value));
+            map.insert(position, format!("{}", value));
         } else if value.is_alphabetic() {
-            let up = raw_map.values.get(&(position + UP));
-            let down = raw_map.values.get(&(position + DOWN));
-            let left = raw_map.values.get(&(position + LEFT));
-            let right = raw_map.values.get(&(position + RIGHT));
+            let up = raw_map.values().get(&(position + UP));
+            let down = raw_map.values().get(&(position + DOWN));
+            let left = raw_map.values().get(&(position + LEFT));
+            let right = raw_map.values().get(&(position + RIGHT));
 
             if let Some(neightboor_value) = match (up, down, left, right) {
                 (Some(c), Some('.'), _, _) if c.is_alphabetic() => Some(format!("{}{}", c, value)),
@@ -152,24 +137,13 @@ fn parse_map(raw_map: &Map<char>) -> Map<String> {
                 (_, _, Some('.'), Some(c)) if c.is_alphabetic() => Some(format!("{}{}", value, c)),
                 _ => None,
             } {
-                map.values.insert(position, neightboor_value);
+                map.insert(position, neightboor_value);
             }
         }
     }
     map
 }
 
-fn parse_raw_map(input: Vec<Vec<char>>) -> Map<char> {
-    let mut raw_map = Map::with_default_formatters('?');
-    for (y, line) in input.iter().enumerate() {
-        for (x, value) in line.iter().enumerate() {
-            let position = Point::new(x as isize, y as isize);
-            raw_map.values.insert(position, *value);
-        }
-    }
-    raw_map
-}
-
 #[cfg(test)]
 mod tests {
     use crate::read_file;
@@ -276,7 +250,7 @@ RE....#.#                           #......RF
 
     #[test]
     fn check_example1() {
-        assert_eq!(step1(EXAMPLE_1.to_string()), Some(26));
+        assert_eq!(step1(EXAMPLE_1.to_string()), Some(23));
     }
 
     #[test]
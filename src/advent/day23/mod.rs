@@ -20,12 +20,10 @@ pub fn execute(input: Vec<isize>, stop_on_first_nat_value: bool) -> Option<isize
             } else {
                 idle = false;
             }
-            computers[id].execute();
-            while computers[id].output.len() >= 3 {
+            let packets: Vec<Vec<isize>> = computers[id].chunks(3).collect();
+            for packet in packets {
                 idle = false;
-                let dest = computers[id].output.pop_front().unwrap();
-                let x = computers[id].output.pop_front().unwrap();
-                let y = computers[id].output.pop_front().unwrap();
+                let (dest, x, y) = (packet[0], packet[1], packet[2]);
                 if dest == 255 {
                     if stop_on_first_nat_value {
                         return Some(y);
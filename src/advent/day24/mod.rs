@@ -1,11 +1,10 @@
 use std::collections::BTreeSet;
 
+use crate::advent::geometry::direction::Direction;
+use crate::advent::geometry::rect::Rect;
+use crate::advent::geometry::Map;
 use crate::advent::geometry::Point;
-use crate::advent::geometry::DOWN;
-use crate::advent::geometry::LEFT;
 use crate::advent::geometry::ORIGIN;
-use crate::advent::geometry::RIGHT;
-use crate::advent::geometry::UP;
 use crate::read_file;
 
 #[derive(Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
@@ -38,35 +37,34 @@ pub fn step2() -> usize {
 }
 
 fn get_adjacents_step1(position: LPoint) -> Vec<LPoint> {
-    [UP, DOWN, LEFT, RIGHT]
+    position
+        .p
+        .neighbours4()
         .iter()
-        .map(|&dir| LPoint {
-            p: position.p + dir,
-            l: position.l,
-        })
-        .filter(|adjacent| adjacent.p.is_in(ORIGIN, LIMIT))
+        .map(|&p| LPoint { p, l: position.l })
+        .filter(|adjacent| BOARD.contains(&adjacent.p))
         .collect()
 }
 
 fn get_adjacents_step2(position: LPoint) -> Vec<LPoint> {
     let mut result = vec![];
-    for &dir in &[UP, DOWN, LEFT, RIGHT] {
+    for &direction in &Direction::CARDINALS {
         let adjacent = LPoint {
-            p: position.p + dir,
+            p: position.p + direction.to_point(),
             l: position.l,
         };
         if adjacent.p.x == 2 && adjacent.p.y == 2 {
             for i in 0..5 {
-                match dir {
-                    UP => result.push(LPoint {
+                match direction {
+                    Direction::North => result.push(LPoint {
                         p: Point::new(i, 4),
                         l: position.l - 1,
                     }),
-                    DOWN => result.push(LPoint {
+                    Direction::South => result.push(LPoint {
                         p: Point::new(i, 0),
                         l: position.l - 1,
                     }),
-                    LEFT => result.push(LPoint {
+                    Direction::West => result.push(LPoint {
                         p: Point::new(4, i),
                         l: position.l - 1,
                     }),
@@ -76,11 +74,11 @@ fn get_adjacents_step2(position: LPoint) -> Vec<LPoint> {
                     }),
                 };
             }
-        } else if adjacent.p.is_in(ORIGIN, LIMIT) {
+        } else if BOARD.contains(&adjacent.p) {
             result.push(adjacent);
         } else {
             result.push(LPoint {
-                p: Point::new(2, 2) + dir,
+                p: Point::new(2, 2) + direction.to_point(),
                 l: position.l + 1,
             });
         }
@@ -120,22 +118,23 @@ fn execute(
     next
 }
 
-const LIMIT: Point = Point { x: 4, y: 4 };
+const BOARD: Rect = Rect {
+    top_left: ORIGIN,
+    bottom_right: Point { x: 4, y: 4 },
+};
 
 fn parse_input(input: String) -> BTreeSet<LPoint> {
-    let mut result = BTreeSet::new();
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, value)| {
-            if value == '#' {
-                result.insert(LPoint {
-                    p: Point::new(x as isize, y as isize),
-                    l: 0,
-                });
-            }
-        })
-    });
-
-    result
+    let map = Map::parse_with(&input, ORIGIN, &|value| match value {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(format!("unexpected {}", value)),
+    })
+    .unwrap_or_else(|e| panic!("{}", e));
+    map.values()
+        .iter()
+        .filter(|&(_, &bug)| bug)
+        .map(|(&p, _)| LPoint { p, l: 0 })
+        .collect()
 }
 
 fn biodiversity_rating(map: BTreeSet<LPoint>) -> usize {
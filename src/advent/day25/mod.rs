@@ -164,12 +164,7 @@ pub fn execute(input: Vec<isize>) -> usize {
 }
 
 fn run_program(prog: &mut Program) -> String {
-    prog.execute();
-    let mut result = String::new();
-    while let Some(output) = prog.output.pop_front() {
-        result.push(output as u8 as char);
-    }
-    result
+    prog.outputs().map(|output| output as u8 as char).collect()
 }
 
 #[cfg(test)]
@@ -0,0 +1,119 @@
+use std::str::FromStr;
+
+use crate::advent::geometry::Point;
+
+// Clockwise from north, north being UP (y decreasing)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    pub const CARDINALS: [Direction; 4] = [North, East, South, West];
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    // Quarter turns : diagonals stay diagonals
+    pub fn turn_right(self) -> Self {
+        self.turn(2)
+    }
+    pub fn turn_left(self) -> Self {
+        self.turn(6)
+    }
+    pub fn reverse(self) -> Self {
+        self.turn(4)
+    }
+    fn turn(self, eighths: usize) -> Self {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+    pub fn to_point(self) -> Point {
+        match self {
+            North => Point::new(0, -1),
+            NorthEast => Point::new(1, -1),
+            East => Point::new(1, 0),
+            SouthEast => Point::new(1, 1),
+            South => Point::new(0, 1),
+            SouthWest => Point::new(-1, 1),
+            West => Point::new(-1, 0),
+            NorthWest => Point::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(direction: Direction) -> Self {
+        direction.to_point()
+    }
+}
+
+// U/D/L/R, up/down/left/right, ^>v<, N/NE/E/... or compass words, case insensitive
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().replace(['-', ' '], "").as_str() {
+            "u" | "up" | "^" | "n" | "north" => Ok(North),
+            "ne" | "northeast" => Ok(NorthEast),
+            "r" | "right" | ">" | "e" | "east" => Ok(East),
+            "se" | "southeast" => Ok(SouthEast),
+            "d" | "down" | "v" | "s" | "south" => Ok(South),
+            "sw" | "southwest" => Ok(SouthWest),
+            "l" | "left" | "<" | "w" | "west" => Ok(West),
+            "nw" | "northwest" => Ok(NorthWest),
+            _ => Err(format!("unknown direction {}", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::geometry::UP;
+
+    #[test]
+    fn turns() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(NorthWest.turn_right(), NorthEast);
+        assert_eq!(SouthEast.reverse(), NorthWest);
+        for direction in &Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), *direction);
+            assert_eq!(direction.reverse(), direction.turn_right().turn_right());
+            assert_eq!(
+                direction.turn_right().to_point(),
+                direction.to_point().rotate(true)
+            );
+        }
+        assert_eq!(Point::from(North), UP);
+    }
+
+    #[test]
+    fn parse() {
+        for (values, direction) in &[
+            (["U", "^", "N", "up"], North),
+            (["R", ">", "e", "East"], East),
+            (["D", "v", "S", "SOUTH"], South),
+            (["L", "<", "W", "west"], West),
+        ] {
+            for value in values {
+                assert_eq!(value.parse(), Ok(*direction));
+            }
+        }
+        assert_eq!("north-east".parse(), Ok(NorthEast));
+        assert_eq!("SW".parse(), Ok(SouthWest));
+        assert_eq!(
+            "sideways".parse::<Direction>(),
+            Err("unknown direction sideways".to_string())
+        );
+    }
+}
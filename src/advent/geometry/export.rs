@@ -0,0 +1,224 @@
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::advent::geometry::Map;
+use crate::advent::geometry::Point;
+
+// Where the days save their pictures
+pub const REPORTS: &str = "target/reports";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Colour {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Colour {
+    pub const BLACK: Colour = Colour::grey(0);
+    pub const WHITE: Colour = Colour::grey(255);
+
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Colour { red, green, blue }
+    }
+    pub const fn grey(level: u8) -> Self {
+        Colour::new(level, level, level)
+    }
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+// Plain Netpbm lines must not be longer than 70 characters
+fn netpbm(header: String, samples: Vec<String>) -> String {
+    let mut result = header;
+    let mut line = String::new();
+    for sample in samples {
+        if !line.is_empty() && line.len() + 1 + sample.len() > 70 {
+            result.push_str(&line);
+            result.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&sample);
+    }
+    if !line.is_empty() {
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
+}
+
+// Every cell of the bounds becomes a scale x scale square, missing cells are given as None
+impl<T> Map<T>
+where
+    T: Display,
+{
+    fn pixels<P: Clone>(
+        &self,
+        scale: usize,
+        pixel: &dyn Fn(Option<&T>) -> P,
+    ) -> (usize, usize, Vec<P>) {
+        let bounds = self.bounds();
+        let mut pixels = Vec::with_capacity(bounds.area() * scale * scale);
+        for y in bounds.top_left.y..=bounds.bottom_right.y {
+            let row = (bounds.top_left.x..=bounds.bottom_right.x)
+                .map(|x| pixel(self.values.get(&Point::new(x, y))))
+                .collect::<Vec<P>>();
+            for _ in 0..scale {
+                for value in &row {
+                    pixels.extend((0..scale).map(|_| value.clone()));
+                }
+            }
+        }
+        (bounds.width() * scale, bounds.height() * scale, pixels)
+    }
+
+    // Plain PBM, black where the mapping is true
+    pub fn to_pbm(&self, scale: usize, black: &dyn Fn(Option<&T>) -> bool) -> String {
+        let (width, height, pixels) = self.pixels(scale, black);
+        netpbm(
+            format!("P1\n{} {}\n", width, height),
+            pixels
+                .iter()
+                .map(|&black| if black { "1" } else { "0" }.to_string())
+                .collect(),
+        )
+    }
+
+    // Plain PGM, from black (0) to white (255)
+    pub fn to_pgm(&self, scale: usize, grey: &dyn Fn(Option<&T>) -> u8) -> String {
+        let (width, height, pixels) = self.pixels(scale, grey);
+        netpbm(
+            format!("P2\n{} {}\n255\n", width, height),
+            pixels.iter().map(|level| level.to_string()).collect(),
+        )
+    }
+
+    // Plain PPM
+    pub fn to_ppm(&self, scale: usize, colour: &dyn Fn(Option<&T>) -> Colour) -> String {
+        let (width, height, pixels) = self.pixels(scale, colour);
+        netpbm(
+            format!("P3\n{} {}\n255\n", width, height),
+            pixels
+                .iter()
+                .flat_map(|c| vec![c.red, c.green, c.blue])
+                .map(|sample| sample.to_string())
+                .collect(),
+        )
+    }
+
+    // One rect for each run of cells of the same colour on a row
+    pub fn to_svg(&self, scale: usize, colour: &dyn Fn(Option<&T>) -> Colour) -> String {
+        let bounds = self.bounds();
+        let mut result = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">\n",
+            bounds.width() * scale,
+            bounds.height() * scale
+        );
+        let (_, _, cells) = self.pixels(1, colour);
+        for (y, row) in cells.chunks(bounds.width()).enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let run = row[x..].iter().take_while(|&&c| c == row[x]).count();
+                result.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                    x * scale,
+                    y * scale,
+                    run * scale,
+                    scale,
+                    row[x].to_hex()
+                ));
+                x += run;
+            }
+        }
+        result.push_str("</svg>\n");
+        result
+    }
+}
+
+// Writes the picture in the reports directory
+pub fn save(name: &str, content: &str) -> io::Result<()> {
+    save_in(Path::new(REPORTS), name, content)
+}
+
+pub fn save_in(directory: &Path, name: &str, content: &str) -> io::Result<()> {
+    fs::create_dir_all(directory)?;
+    fs::write(directory.join(name), content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> Map<char> {
+        "#.\n.o".parse().unwrap()
+    }
+
+    #[test]
+    fn netpbm_formats() {
+        let map = map();
+        assert_eq!(map.to_pbm(1, &|v| v == Some(&'#')), "P1\n2 2\n1 0 0 0\n");
+        assert_eq!(
+            map.to_pbm(2, &|v| v != Some(&'.')),
+            "P1\n4 4\n1 1 0 0 1 1 0 0 0 0 1 1 0 0 1 1\n"
+        );
+        assert_eq!(
+            map.to_pgm(1, &|v| match v {
+                Some('#') => 0,
+                Some('o') => 128,
+                _ => 255,
+            }),
+            "P2\n2 2\n255\n0 255 255 128\n"
+        );
+        assert_eq!(
+            map.to_ppm(1, &|v| match v {
+                Some('o') => Colour::new(255, 0, 0),
+                _ => Colour::WHITE,
+            }),
+            "P3\n2 2\n255\n255 255 255 255 255 255 255 255 255 255 0 0\n"
+        );
+        // wrapped to 70 characters
+        let long: Map<char> = ".".repeat(40).parse().unwrap();
+        let pbm = long.to_pbm(1, &|_| false);
+        assert!(pbm.lines().all(|line| line.len() <= 70));
+        assert_eq!(pbm.lines().nth(2).unwrap().len(), 69);
+    }
+
+    #[test]
+    fn svg_runs() {
+        let map: Map<char> = "##.\n...".parse().unwrap();
+        let svg = map.to_svg(10, &|v| match v {
+            Some('#') => Colour::BLACK,
+            _ => Colour::new(0, 128, 255),
+        });
+        assert_eq!(
+            svg,
+            [
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"20\">",
+                "<rect x=\"0\" y=\"0\" width=\"20\" height=\"10\" fill=\"#000000\"/>",
+                "<rect x=\"20\" y=\"0\" width=\"10\" height=\"10\" fill=\"#0080ff\"/>",
+                "<rect x=\"0\" y=\"10\" width=\"30\" height=\"10\" fill=\"#0080ff\"/>",
+                "</svg>\n",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn pictures_are_written_in_the_directory() {
+        let directory =
+            std::env::temp_dir().join(format!("aoc-2019-reports-{}", std::process::id()));
+        let picture = map().to_pbm(1, &|cell| cell == Some(&'#'));
+        save_in(&directory, "map.pbm", &picture).unwrap();
+        assert_eq!(
+            fs::read_to_string(directory.join("map.pbm")).unwrap(),
+            picture
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use crate::advent::geometry::Map;
+use crate::advent::geometry::Point;
+use crate::advent::search;
+
+// Areas of the map made of passable cells joined by their sides
+impl<T> Map<T>
+where
+    T: Display,
+{
+    // Steps from the source to every reachable cell
+    pub fn distance_field(
+        &self,
+        source: Point,
+        passable: &dyn Fn(&T) -> bool,
+    ) -> HashMap<Point, usize> {
+        search::bfs(source, &search::plane_successors(self, passable), &|_| {
+            false
+        })
+        .distances
+    }
+
+    // Reachable cells, start included
+    pub fn flood_fill(&self, start: Point, passable: &dyn Fn(&T) -> bool) -> HashSet<Point> {
+        self.distance_field(start, passable).into_keys().collect()
+    }
+
+    // Passable cells grouped by area, ordered by their first cell in reading order
+    pub fn components(&self, passable: &dyn Fn(&T) -> bool) -> Vec<HashSet<Point>> {
+        let mut cells = self
+            .values
+            .iter()
+            .filter(|(_, value)| passable(value))
+            .map(|(&point, _)| point)
+            .collect::<Vec<Point>>();
+        cells.sort();
+        let mut components: Vec<HashSet<Point>> = vec![];
+        for cell in cells {
+            if components
+                .iter()
+                .all(|component| !component.contains(&cell))
+            {
+                components.push(self.flood_fill(cell, passable));
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAVES: &str = "\
+#####
+#.#.#
+#.#.#
+##..#
+#.###";
+
+    #[test]
+    fn fill_and_distances() {
+        let map: Map<char> = CAVES.parse().unwrap();
+        let open = |&c: &char| c == '.';
+        let field = map.distance_field(Point::new(3, 1), &open);
+        assert_eq!(field.len(), 4);
+        assert_eq!(field[&Point::new(2, 3)], 3);
+        assert_eq!(field.get(&Point::new(1, 1)), None);
+        assert_eq!(
+            map.flood_fill(Point::new(1, 2), &open),
+            vec![Point::new(1, 1), Point::new(1, 2)]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn label_components() {
+        let map: Map<char> = CAVES.parse().unwrap();
+        let components = map.components(&|&c| c == '.');
+        assert_eq!(
+            components.iter().map(HashSet::len).collect::<Vec<usize>>(),
+            vec![2, 4, 1]
+        );
+        assert!(components[0].contains(&Point::new(1, 1)));
+        assert!(components[2].contains(&Point::new(1, 4)));
+        assert_eq!(map.components(&|&c| c == '#').len(), 1);
+    }
+}
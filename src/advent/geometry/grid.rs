@@ -0,0 +1,203 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::ops::Index;
+use std::ops::IndexMut;
+
+use crate::advent::geometry::default_nl_formatter;
+use crate::advent::geometry::default_value_formatter;
+use crate::advent::geometry::Map;
+use crate::advent::geometry::Plane;
+use crate::advent::geometry::Point;
+
+// Dense row-major storage for bounded planes : every cell from origin to origin + (width, height) exists
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    origin: Point,
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(origin: Point, width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            origin,
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.index_of(point).map(move |idx| &mut self.cells[idx])
+    }
+    fn index_of(&self, point: &Point) -> Option<usize> {
+        let x = point.x - self.origin.x;
+        let y = point.y - self.origin.y;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+    fn point_of(&self, idx: usize) -> Point {
+        Point::new(
+            self.origin.x + (idx % self.width) as isize,
+            self.origin.y + (idx / self.width) as isize,
+        )
+    }
+}
+
+impl<T> Plane<T> for Grid<T> {
+    fn get(&self, point: &Point) -> Option<&T> {
+        self.index_of(point).map(|idx| &self.cells[idx])
+    }
+    // Out of bounds points can't be stored : the grid doesn't grow
+    fn set(&mut self, point: Point, value: T) {
+        match self.get_mut(&point) {
+            Some(cell) => *cell = value,
+            None => panic!("{:?} is outside of the grid", point),
+        }
+    }
+    fn corners(&self) -> (Point, Point) {
+        (
+            self.origin,
+            Point::new(
+                self.origin.x + self.width as isize - 1,
+                self.origin.y + self.height as isize - 1,
+            ),
+        )
+    }
+    fn points(&self) -> Vec<Point> {
+        (0..self.cells.len())
+            .map(|idx| self.point_of(idx))
+            .collect()
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(&point)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(&point)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", point))
+    }
+}
+
+// Missing points of the map are filled with its default value
+impl<T> From<&Map<T>> for Grid<T>
+where
+    T: Display + Clone,
+{
+    fn from(map: &Map<T>) -> Self {
+        let (top_left, bottom_right) = map.corners();
+        let mut grid = Grid::new(
+            top_left,
+            (bottom_right.x - top_left.x + 1) as usize,
+            (bottom_right.y - top_left.y + 1) as usize,
+            map.default_value.clone(),
+        );
+        for (point, value) in &map.values {
+            grid[*point] = value.clone();
+        }
+        grid
+    }
+}
+
+impl<T> From<Grid<T>> for Map<T>
+where
+    T: Display + Default,
+{
+    fn from(grid: Grid<T>) -> Self {
+        let mut map = Map::new(default_value_formatter(), default_nl_formatter());
+        let points = grid.points();
+        for (point, value) in points.into_iter().zip(grid.cells) {
+            map.insert(point, value);
+        }
+        map
+    }
+}
+
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Written once for both storages
+    fn count<P: Plane<char>>(plane: &P, value: char) -> usize {
+        plane
+            .points()
+            .iter()
+            .filter(|p| plane.get(p) == Some(&value))
+            .count()
+    }
+
+    #[test]
+    fn negative_origin() {
+        let mut grid = Grid::new(Point::new(-2, -1), 4, 3, '.');
+        grid.set(Point::new(-2, -1), '#');
+        grid[Point::new(1, 1)] = '#';
+        assert_eq!(grid.get(&Point::new(1, 1)), Some(&'#'));
+        assert_eq!(grid.get(&Point::new(2, 1)), None);
+        assert_eq!(grid.get(&Point::new(-3, 0)), None);
+        assert_eq!(grid.corners(), (Point::new(-2, -1), Point::new(1, 1)));
+        assert_eq!(grid.to_string(), "#...\n....\n...#\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_outside() {
+        Grid::new(Point::new(0, 0), 2, 2, 0).set(Point::new(2, 0), 1);
+    }
+
+    #[test]
+    fn convert_map_and_grid() {
+        let mut map = Map::with_default_formatters(' ');
+        map.insert(Point::new(-1, 3), '#');
+        map.insert(Point::new(1, 4), '#');
+        map.insert(Point::new(0, 4), '.');
+        let grid = Grid::from(&map);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.to_string(), "#  \n .#\n");
+        assert_eq!(count(&grid, '#'), count(&map, '#'));
+        assert_eq!(grid.corners(), map.corners());
+
+        let back = Map::from(grid);
+        assert_eq!(back.values.len(), 6);
+        assert_eq!(back.to_string(), map.to_string());
+        assert_eq!(back.find_with(&|_, v| *v == '.'), Some(Point::new(0, 4)));
+    }
+}
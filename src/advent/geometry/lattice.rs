@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+
+use num::Integer;
+
+use crate::advent::geometry::Point;
+use crate::advent::geometry::ORIGIN;
+
+// Exact directions between points of the integer lattice : no floats, no rounding
+
+// The smallest lattice step pointing the same way, ORIGIN stays ORIGIN
+pub fn reduce(delta: Point) -> Point {
+    match delta.x.gcd(&delta.y) {
+        0 => delta,
+        gcd => delta / gcd,
+    }
+}
+
+pub fn direction(from: &Point, to: &Point) -> Point {
+    reduce(*to - *from)
+}
+
+// Order of the directions met by a beam turning clockwise from UP (y grows downward).
+// ORIGIN has no direction and is rejected
+pub fn clockwise_cmp(a: &Point, b: &Point) -> Ordering {
+    debug_assert!(
+        *a != ORIGIN && *b != ORIGIN,
+        "ORIGIN has no clockwise angle"
+    );
+    // right half, UP included, then left half, DOWN included
+    let half = |p: &Point| {
+        if p.x > 0 || (p.x == 0 && p.y < 0) {
+            0
+        } else {
+            1
+        }
+    };
+    half(a)
+        .cmp(&half(b))
+        // cross product, in i128 so that far away directions don't overflow
+        .then_with(|| 0.cmp(&(a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128)))
+}
+
+// Lattice points crossed by the segment, both ends included
+pub fn segment(from: &Point, to: &Point) -> Vec<Point> {
+    let delta = *to - *from;
+    let step = reduce(delta);
+    let count = delta.x.gcd(&delta.y);
+    (0..=count).map(|k| *from + step * k).collect()
+}
+
+// Blocker stands strictly between the observer and the target
+pub fn occludes(observer: &Point, blocker: &Point, target: &Point) -> bool {
+    blocker != observer
+        && blocker != target
+        && direction(observer, blocker) == direction(observer, target)
+        && (*blocker - *observer).chebyshev_norm() < (*target - *observer).chebyshev_norm()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduce_directions() {
+        assert_eq!(reduce(Point::new(6, -4)), Point::new(3, -2));
+        assert_eq!(reduce(Point::new(0, -7)), Point::new(0, -1));
+        assert_eq!(reduce(ORIGIN), ORIGIN);
+        // angles too close for f64 to tell them apart
+        let far = 1 << 40;
+        assert_ne!(
+            direction(&ORIGIN, &Point::new(far, far + 1)),
+            direction(&ORIGIN, &Point::new(far + 1, far + 2))
+        );
+    }
+
+    #[test]
+    fn clockwise_order() {
+        let mut directions = vec![
+            Point::new(-1, -1),
+            Point::new(-1, 0),
+            Point::new(0, 1),
+            Point::new(1, 2),
+            Point::new(1, 0),
+            Point::new(2, -1),
+            Point::new(1, -1),
+            Point::new(0, -1),
+            Point::new(-1, 1),
+        ];
+        directions.sort_by(clockwise_cmp);
+        assert_eq!(
+            directions,
+            vec![
+                Point::new(0, -1),
+                Point::new(1, -1),
+                Point::new(2, -1),
+                Point::new(1, 0),
+                Point::new(1, 2),
+                Point::new(0, 1),
+                Point::new(-1, 1),
+                Point::new(-1, 0),
+                Point::new(-1, -1),
+            ]
+        );
+    }
+
+    #[test]
+    fn clockwise_order_of_far_directions() {
+        let far = 1 << 40;
+        let mut directions = vec![
+            Point::new(-far, -far - 1),
+            Point::new(far + 1, far),
+            Point::new(far, far + 1),
+            Point::new(far, -far - 1),
+        ];
+        directions.sort_by(clockwise_cmp);
+        assert_eq!(
+            directions,
+            vec![
+                Point::new(far, -far - 1),
+                Point::new(far + 1, far),
+                Point::new(far, far + 1),
+                Point::new(-far, -far - 1),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "ORIGIN has no clockwise angle")]
+    fn origin_has_no_clockwise_angle() {
+        clockwise_cmp(&ORIGIN, &Point::new(-1, 0));
+    }
+
+    #[test]
+    fn line_of_sight() {
+        assert_eq!(
+            segment(&Point::new(1, 1), &Point::new(7, -3)),
+            vec![Point::new(1, 1), Point::new(4, -1), Point::new(7, -3)]
+        );
+        assert_eq!(segment(&ORIGIN, &ORIGIN), vec![ORIGIN]);
+        assert!(occludes(&ORIGIN, &Point::new(4, -2), &Point::new(6, -3)));
+        assert!(!occludes(&ORIGIN, &Point::new(6, -3), &Point::new(4, -2)));
+        assert!(!occludes(&ORIGIN, &Point::new(5, -2), &Point::new(10, -5)));
+        assert!(!occludes(&ORIGIN, &Point::new(-2, 1), &Point::new(4, -2)));
+    }
+}
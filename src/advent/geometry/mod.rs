@@ -0,0 +1,308 @@
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+
+use crate::advent::geometry::direction::Direction;
+use crate::advent::geometry::rect::Rect;
+
+pub mod direction;
+pub mod export;
+pub mod fill;
+pub mod grid;
+pub mod lattice;
+pub mod parse;
+pub mod rect;
+pub mod transform;
+pub mod vector;
+
+pub const UP: Point = Point { x: 0, y: -1 };
+pub const DOWN: Point = Point { x: 0, y: 1 };
+pub const RIGHT: Point = Point { x: 1, y: 0 };
+pub const LEFT: Point = Point { x: -1, y: 0 };
+pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+pub const D3_ORIGIN: D3Point = D3Point { x: 0, y: 0, z: 0 };
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct D3Point {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+type ValueFormatter<T> = fn(&mut Formatter<'_>, Option<&T>) -> Result<(), Error>;
+type NewLineFormatter = fn(&mut Formatter<'_>, usize) -> Result<(), Error>;
+
+pub fn default_value_formatter<T>() -> ValueFormatter<T>
+where
+    T: Display,
+{
+    |f, v| write!(f, "{}", v.unwrap())
+}
+
+pub fn default_nl_formatter() -> NewLineFormatter {
+    |f, _| writeln!(f)
+}
+
+#[derive(Clone)]
+pub struct Map<T>
+where
+    T: Display,
+{
+    // written only through insert and remove, which keep the bounds in sync
+    values: HashMap<Point, T>,
+    pub formatter: ValueFormatter<T>,
+    pub nl_formatter: NewLineFormatter,
+    default_value: T,
+    // None when it must be computed again
+    bounds: Cell<Option<Rect>>,
+}
+
+impl<T> Map<T>
+where
+    T: Display + Default,
+{
+    pub fn new(formatter: ValueFormatter<T>, nl_formatter: NewLineFormatter) -> Self {
+        Map {
+            values: HashMap::new(),
+            formatter,
+            nl_formatter,
+            default_value: T::default(),
+            bounds: Cell::new(None),
+        }
+    }
+    pub fn with_default_formatters(default_value: T) -> Self
+    where
+        T: Clone,
+    {
+        Map {
+            values: HashMap::new(),
+            formatter: default_value_formatter::<T>(),
+            nl_formatter: default_nl_formatter(),
+            default_value,
+            bounds: Cell::new(None),
+        }
+    }
+    // Distance between the extreme rows
+    pub fn height(&self) -> usize {
+        self.bounds().height() - 1
+    }
+    // Distance between the extreme columns
+    pub fn width(&self) -> usize {
+        self.bounds().width() - 1
+    }
+    pub fn find(&self, value: T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.values
+            .iter()
+            .find(|&(_, val)| *val == value)
+            .map(|(&pos, _)| pos)
+    }
+}
+
+impl<T> Map<T>
+where
+    T: Display,
+{
+    pub fn values(&self) -> &HashMap<Point, T> {
+        &self.values
+    }
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        let bounds = if self.values.is_empty() {
+            Some(Rect::new(point, point))
+        } else {
+            self.bounds.get().map(|bounds| bounds.including(&point))
+        };
+        self.bounds.set(bounds);
+        self.values.insert(point, value)
+    }
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        if let Some(bounds) = self.bounds.get() {
+            // shrinks only when the point was on an edge
+            let Rect {
+                top_left,
+                bottom_right,
+            } = bounds;
+            if [top_left.x, bottom_right.x].contains(&point.x)
+                || [top_left.y, bottom_right.y].contains(&point.y)
+            {
+                self.bounds.set(None);
+            }
+        }
+        self.values.remove(point)
+    }
+    // Smallest rect holding every value, ORIGIN alone when empty
+    pub fn bounds(&self) -> Rect {
+        match self.bounds.get() {
+            Some(bounds) => bounds,
+            None => {
+                let bounds =
+                    Rect::from_points(self.values.keys()).unwrap_or(Rect::new(ORIGIN, ORIGIN));
+                self.bounds.set(Some(bounds));
+                bounds
+            }
+        }
+    }
+}
+
+// Storage agnostic access to a 2D plane, implemented by the sparse Map and the dense Grid
+pub trait Plane<T> {
+    fn get(&self, point: &Point) -> Option<&T>;
+    fn set(&mut self, point: Point, value: T);
+    // Inclusive top left and bottom right corners
+    fn corners(&self) -> (Point, Point);
+    fn points(&self) -> Vec<Point>;
+
+    fn contains(&self, point: &Point) -> bool {
+        self.get(point).is_some()
+    }
+    // Present cells among the 4 neighbours
+    fn neighbours(&self, point: &Point) -> Vec<(Point, &T)> {
+        point
+            .neighbours4()
+            .iter()
+            .filter_map(|p| self.get(p).map(|v| (*p, v)))
+            .collect()
+    }
+    fn find_with(&self, predicate: &dyn Fn(&Point, &T) -> bool) -> Option<Point> {
+        self.points()
+            .into_iter()
+            .find(|p| self.get(p).is_some_and(|v| predicate(p, v)))
+    }
+}
+
+impl<T> Plane<T> for Map<T>
+where
+    T: Display,
+{
+    fn get(&self, point: &Point) -> Option<&T> {
+        self.values.get(point)
+    }
+    fn set(&mut self, point: Point, value: T) {
+        self.insert(point, value);
+    }
+    fn corners(&self) -> (Point, Point) {
+        let bounds = self.bounds();
+        (bounds.top_left, bounds.bottom_right)
+    }
+    fn points(&self) -> Vec<Point> {
+        self.values.keys().cloned().collect()
+    }
+}
+
+impl<T> Display for Map<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let bounds = self.bounds();
+        for point in bounds.points() {
+            (self.formatter)(f, self.values.get(&point).or(Some(&self.default_value)))?;
+            if point.x == bounds.bottom_right.x {
+                (self.nl_formatter)(f, bounds.width() - 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+    pub fn manhattan_distance_from(&self, other: &Point) -> usize {
+        (self.x - other.x).abs() as usize + (self.y - other.y).abs() as usize
+    }
+    pub fn angle_with(&self, other: &Point) -> f64 {
+        (self.x as f64 - other.x as f64).atan2(self.y as f64 - other.y as f64)
+    }
+    pub fn rotate(&self, clockwise: bool) -> Point {
+        if clockwise {
+            Point::new(-self.y, self.x)
+        } else {
+            Point::new(self.y, -self.x)
+        }
+    }
+    pub fn is_in(&self, start: Point, end: Point) -> bool {
+        self.x >= start.x && self.x <= end.x && self.y >= start.y && self.y <= end.y
+    }
+    // Clockwise from UP
+    pub fn neighbours4(&self) -> [Point; 4] {
+        Direction::CARDINALS.map(|direction| *self + direction.to_point())
+    }
+    // Clockwise from UP, diagonals included
+    pub fn neighbours8(&self) -> [Point; 8] {
+        Direction::ALL.map(|direction| *self + direction.to_point())
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
+        let partial_cmp_other_y = self.y.partial_cmp(&other.y);
+        if let Some(cmp_other_y) = partial_cmp_other_y {
+            if cmp_other_y == Ordering::Equal {
+                return self.x.partial_cmp(&other.x);
+            } else {
+                return partial_cmp_other_y;
+            }
+        }
+        None
+    }
+}
+
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_map_size() {
+        let mut map = Map::new(|_, _| Ok(()), |_, _| Ok(()));
+        map.insert(Point::new(0, 1), 1);
+        map.insert(Point::new(-1, -1), 1);
+        assert_eq!(map.height(), 2);
+        assert_eq!(map.width(), 1);
+    }
+
+    #[test]
+    fn check_map_bounds() {
+        let mut map = Map::with_default_formatters('.');
+        assert_eq!(map.bounds(), Rect::new(ORIGIN, ORIGIN));
+        map.insert(Point::new(3, 4), '#');
+        assert_eq!(map.bounds(), Rect::new(Point::new(3, 4), Point::new(3, 4)));
+        map.insert(Point::new(1, 5), '#');
+        map.insert(Point::new(2, 6), '#');
+        assert_eq!(map.bounds(), Rect::new(Point::new(1, 4), Point::new(3, 6)));
+        map.remove(&Point::new(2, 6));
+        assert_eq!(map.bounds(), Rect::new(Point::new(1, 4), Point::new(3, 5)));
+        assert_eq!(map.to_string(), "..#\n#..\n");
+    }
+
+    #[test]
+    fn check_neighbours() {
+        let point = Point::new(2, -3);
+        assert_eq!(
+            point.neighbours4(),
+            [point + UP, point + RIGHT, point + DOWN, point + LEFT]
+        );
+        assert_eq!(point.neighbours8()[1], Point::new(3, -4));
+        let map: Map<char> = "#.\n..".parse().unwrap();
+        assert_eq!(map.neighbours(&ORIGIN), vec![(RIGHT, &'.'), (DOWN, &'.')]);
+    }
+}
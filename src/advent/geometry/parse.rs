@@ -0,0 +1,101 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crate::advent::geometry::default_nl_formatter;
+use crate::advent::geometry::default_value_formatter;
+use crate::advent::geometry::Map;
+use crate::advent::geometry::Point;
+use crate::advent::geometry::ORIGIN;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "line {}, column {} : {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl<T> Map<T>
+where
+    T: Display + Default,
+{
+    // One cell per character, the first one of the content at origin. Lines may have different
+    // lengths and their trailing whitespaces are ignored. Lines and columns of errors start at 1
+    pub fn parse_with(
+        content: &str,
+        origin: Point,
+        convert: &dyn Fn(char) -> Result<T, String>,
+    ) -> Result<Self, ParseError> {
+        let mut map = Map::new(default_value_formatter(), default_nl_formatter());
+        for (y, line) in content.lines().enumerate() {
+            for (x, value) in line.trim_end().chars().enumerate() {
+                let value = convert(value).map_err(|message| ParseError {
+                    line: y + 1,
+                    column: x + 1,
+                    message,
+                })?;
+                map.insert(
+                    Point::new(origin.x + x as isize, origin.y + y as isize),
+                    value,
+                );
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl FromStr for Map<char> {
+    type Err = ParseError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut map = Map::parse_with(content, ORIGIN, &Ok)?;
+        map.default_value = ' ';
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ragged_lines() {
+        let map: Map<char> = "#.#  \r\n\n  .#\n#".parse().unwrap();
+        assert_eq!(map.values.len(), 8);
+        assert_eq!(map.values[&Point::new(3, 2)], '#');
+        assert_eq!(map.values[&Point::new(0, 2)], ' ');
+        assert_eq!(map.values.get(&Point::new(3, 0)), None);
+        assert_eq!(map.to_string(), "#.# \n    \n  .#\n#   \n");
+    }
+
+    #[test]
+    fn convert_with_origin() {
+        let bugs = |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(format!("unexpected {}", c)),
+        };
+        let map = Map::parse_with("#.\n.#", Point::new(-1, -1), &bugs).unwrap();
+        assert!(map.values[&Point::new(-1, -1)]);
+        assert!(map.values[&Point::new(0, 0)]);
+        assert!(!map.values[&Point::new(-1, 0)]);
+        assert_eq!(
+            Map::parse_with("#.\n.?#", ORIGIN, &bugs)
+                .err()
+                .unwrap()
+                .to_string(),
+            "line 2, column 2 : unexpected ?"
+        );
+    }
+}
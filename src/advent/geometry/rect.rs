@@ -0,0 +1,115 @@
+use std::cmp::max;
+use std::cmp::min;
+
+use crate::advent::geometry::Point;
+
+// Both corners are included : a rect always holds at least one point
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub top_left: Point,
+    pub bottom_right: Point,
+}
+
+impl Rect {
+    // Any two opposite corners
+    pub fn new(a: Point, b: Point) -> Self {
+        Rect {
+            top_left: Point::new(min(a.x, b.x), min(a.y, b.y)),
+            bottom_right: Point::new(max(a.x, b.x), max(a.y, b.y)),
+        }
+    }
+    // Smallest rect holding every point, None without points
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Rect::new(*first, *first), |rect, p| rect.including(p)))
+    }
+    pub fn width(&self) -> usize {
+        (self.bottom_right.x - self.top_left.x) as usize + 1
+    }
+    pub fn height(&self) -> usize {
+        (self.bottom_right.y - self.top_left.y) as usize + 1
+    }
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+    pub fn contains(&self, point: &Point) -> bool {
+        point.is_in(self.top_left, self.bottom_right)
+    }
+    pub fn including(&self, point: &Point) -> Self {
+        self.union(&Rect::new(*point, *point))
+    }
+    pub fn union(&self, other: &Rect) -> Self {
+        Rect {
+            top_left: self.top_left.componentwise_min(&other.top_left),
+            bottom_right: self.bottom_right.componentwise_max(&other.bottom_right),
+        }
+    }
+    pub fn intersection(&self, other: &Rect) -> Option<Self> {
+        let top_left = self.top_left.componentwise_max(&other.top_left);
+        let bottom_right = self.bottom_right.componentwise_min(&other.bottom_right);
+        if top_left.x <= bottom_right.x && top_left.y <= bottom_right.y {
+            Some(Rect {
+                top_left,
+                bottom_right,
+            })
+        } else {
+            None
+        }
+    }
+    // The closest point of the rect
+    pub fn clamp(&self, point: &Point) -> Point {
+        point
+            .componentwise_max(&self.top_left)
+            .componentwise_min(&self.bottom_right)
+    }
+    // Row by row, from the top left corner
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (top_left, bottom_right) = (self.top_left, self.bottom_right);
+        (top_left.y..=bottom_right.y)
+            .flat_map(move |y| (top_left.x..=bottom_right.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_operations() {
+        let a = Rect::new(Point::new(2, 3), Point::new(-1, 0));
+        assert_eq!(a.top_left, Point::new(-1, 0));
+        assert_eq!((a.width(), a.height(), a.area()), (4, 4, 16));
+        assert!(a.contains(&Point::new(2, 0)));
+        assert!(!a.contains(&Point::new(3, 0)));
+
+        let b = Rect::new(Point::new(1, 2), Point::new(5, 7));
+        assert_eq!(a.union(&b), Rect::new(Point::new(-1, 0), Point::new(5, 7)));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Point::new(1, 2), Point::new(2, 3)))
+        );
+        assert_eq!(
+            a.intersection(&Rect::new(Point::new(3, 0), Point::new(4, 1))),
+            None
+        );
+        assert_eq!(a.clamp(&Point::new(9, -9)), Point::new(2, 0));
+        assert_eq!(a.clamp(&Point::new(0, 1)), Point::new(0, 1));
+    }
+
+    #[test]
+    fn rect_points() {
+        let points = [Point::new(1, 1), Point::new(0, 2), Point::new(1, 2)];
+        let rect = Rect::from_points(&points).unwrap();
+        assert_eq!(
+            rect.points().collect::<Vec<Point>>(),
+            vec![
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(0, 2),
+                Point::new(1, 2)
+            ]
+        );
+        assert_eq!(Rect::from_points(&[]), None);
+    }
+}
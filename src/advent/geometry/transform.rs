@@ -0,0 +1,132 @@
+use std::cell::Cell;
+use std::fmt::Display;
+
+use crate::advent::geometry::rect::Rect;
+use crate::advent::geometry::Map;
+use crate::advent::geometry::Point;
+
+// Rotations, flips and transposition keep the top left corner of the bounds in place
+impl<T> Map<T>
+where
+    T: Display + Clone,
+{
+    // Same formatters and default value, other values
+    fn with_values(&self, values: impl Iterator<Item = (Point, T)>) -> Self {
+        let mut map = Map {
+            values: Default::default(),
+            formatter: self.formatter,
+            nl_formatter: self.nl_formatter,
+            default_value: self.default_value.clone(),
+            bounds: Cell::new(None),
+        };
+        for (point, value) in values {
+            map.insert(point, value);
+        }
+        map
+    }
+
+    fn moved(&self, transform: &dyn Fn(Point) -> Point) -> Self {
+        let top_left = self.bounds().top_left;
+        let moved = self.with_values(
+            self.values
+                .iter()
+                .map(|(&point, value)| (transform(point - top_left), value.clone())),
+        );
+        let offset = top_left - moved.bounds().top_left;
+        moved.translate(offset)
+    }
+
+    // Clockwise for positive quarter turns
+    pub fn rotate(&self, quarter_turns: isize) -> Self {
+        let clockwise = quarter_turns > 0;
+        self.moved(&|mut point| {
+            for _ in 0..quarter_turns.abs() % 4 {
+                point = point.rotate(clockwise);
+            }
+            point
+        })
+    }
+
+    // Left becomes right
+    pub fn flip_horizontal(&self) -> Self {
+        self.moved(&|point| Point::new(-point.x, point.y))
+    }
+
+    // Top becomes bottom
+    pub fn flip_vertical(&self) -> Self {
+        self.moved(&|point| Point::new(point.x, -point.y))
+    }
+
+    // Rows become columns
+    pub fn transpose(&self) -> Self {
+        self.moved(&|point| Point::new(point.y, point.x))
+    }
+
+    // Values inside the rect, at the same positions
+    pub fn crop(&self, rect: &Rect) -> Self {
+        self.with_values(
+            self.values
+                .iter()
+                .filter(|(point, _)| rect.contains(point))
+                .map(|(&point, value)| (point, value.clone())),
+        )
+    }
+
+    pub fn translate(&self, offset: Point) -> Self {
+        self.with_values(
+            self.values
+                .iter()
+                .map(|(&point, value)| (point + offset, value.clone())),
+        )
+    }
+
+    // Copies every value of other, moved by offset, over this map
+    pub fn blit(&mut self, other: &Map<T>, offset: Point) {
+        for (&point, value) in &other.values {
+            self.insert(point + offset, value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(content: &str) -> Map<char> {
+        content
+            .parse::<Map<char>>()
+            .unwrap()
+            .translate(Point::new(3, -2))
+    }
+
+    fn text(map: &Map<char>) -> String {
+        assert_eq!(map.bounds().top_left, Point::new(3, -2));
+        map.to_string()
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let shape = map("ab\ncd\nef");
+        assert_eq!(text(&shape.rotate(1)), "eca\nfdb\n");
+        assert_eq!(text(&shape.rotate(2)), "fe\ndc\nba\n");
+        assert_eq!(text(&shape.rotate(-1)), "bdf\nace\n");
+        assert_eq!(text(&shape.rotate(4)), text(&shape));
+        assert_eq!(text(&shape.flip_horizontal()), "ba\ndc\nfe\n");
+        assert_eq!(text(&shape.flip_vertical()), "ef\ncd\nab\n");
+        assert_eq!(text(&shape.transpose()), "ace\nbdf\n");
+    }
+
+    #[test]
+    fn crop_and_blit() {
+        let mut shape = map("abc\ndef\nghi");
+        let center = Rect::new(Point::new(4, -2), Point::new(5, -1));
+        let cropped = shape.crop(&center);
+        assert_eq!(cropped.bounds(), center);
+        assert_eq!(cropped.to_string(), "bc\nef\n");
+
+        shape.blit(&"##".parse().unwrap(), Point::new(4, 0));
+        assert_eq!(text(&shape), "abc\ndef\ng##\n");
+        shape.blit(&cropped, Point::new(-1, 1));
+        assert_eq!(text(&shape), "abc\nbcf\nef#\n");
+    }
+}
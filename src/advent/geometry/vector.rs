@@ -0,0 +1,340 @@
+use std::ops::Add;
+use std::ops::AddAssign;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+use std::ops::SubAssign;
+use std::str::FromStr;
+
+use crate::advent::geometry::D3Point;
+use crate::advent::geometry::Point;
+
+const AXES: [&str; 4] = ["x", "y", "z", "w"];
+
+// Coordinates of "(1,2)" or "<x=1, y=2>", names are optional but must follow the axes order
+fn parse_coordinates(value: &str, dimensions: usize) -> Result<Vec<isize>, String> {
+    let inner = value.trim();
+    let inner = match (inner.chars().next(), inner.chars().last()) {
+        (Some('('), Some(')')) | (Some('<'), Some('>')) => &inner[1..inner.len() - 1],
+        _ => return Err(format!("{} should be enclosed in () or <>", value)),
+    };
+    let coordinates = inner
+        .split(',')
+        .enumerate()
+        .map(|(idx, coordinate)| {
+            let coordinate = coordinate.trim();
+            let number = match coordinate.split_once('=') {
+                Some((name, number)) if Some(&name.trim()) == AXES.get(idx) => number.trim(),
+                Some((name, _)) => return Err(format!("unexpected axis {}", name.trim())),
+                None => coordinate,
+            };
+            number
+                .parse()
+                .map_err(|_| format!("bad coordinate {}", coordinate))
+        })
+        .collect::<Result<Vec<isize>, String>>()?;
+    if coordinates.len() != dimensions {
+        return Err(format!(
+            "{} should have {} coordinates",
+            value.trim(),
+            dimensions
+        ));
+    }
+    Ok(coordinates)
+}
+
+// Vector algebra, identical for every named-fields point
+macro_rules! vector {
+    ($point:ident, $($axis:ident),+) => {
+        impl $point {
+            pub fn componentwise_min(&self, other: &Self) -> Self {
+                $point { $($axis: self.$axis.min(other.$axis)),+ }
+            }
+            pub fn componentwise_max(&self, other: &Self) -> Self {
+                $point { $($axis: self.$axis.max(other.$axis)),+ }
+            }
+            pub fn signum(&self) -> Self {
+                $point { $($axis: self.$axis.signum()),+ }
+            }
+            pub fn abs(&self) -> Self {
+                $point { $($axis: self.$axis.abs()),+ }
+            }
+            pub fn dot(&self, other: &Self) -> isize {
+                0 $(+ self.$axis * other.$axis)+
+            }
+            pub fn manhattan_norm(&self) -> usize {
+                0 $(+ self.$axis.unsigned_abs())+
+            }
+            pub fn chebyshev_norm(&self) -> usize {
+                0 $(.max(self.$axis.unsigned_abs()))+
+            }
+        }
+
+        impl Add for $point {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($axis: self.$axis + other.$axis),+ }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl Sub for $point {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($axis: self.$axis - other.$axis),+ }
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl Neg for $point {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($axis: -self.$axis),+ }
+            }
+        }
+
+        impl Mul<isize> for $point {
+            type Output = Self;
+
+            fn mul(self, factor: isize) -> Self {
+                $point { $($axis: self.$axis * factor),+ }
+            }
+        }
+
+        // Integer division, rounded toward zero
+        impl Div<isize> for $point {
+            type Output = Self;
+
+            fn div(self, divisor: isize) -> Self {
+                $point { $($axis: self.$axis / divisor),+ }
+            }
+        }
+
+        impl FromStr for $point {
+            type Err = String;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let mut coordinates = parse_coordinates(value, [$(stringify!($axis)),+].len())?
+                    .into_iter();
+                Ok($point { $($axis: coordinates.next().unwrap()),+ })
+            }
+        }
+    };
+}
+
+vector!(Point, x, y);
+vector!(D3Point, x, y, z);
+
+// Any number of dimensions, x y z w being the first four
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<const N: usize>(pub [isize; N]);
+
+impl<const N: usize> PointN<N> {
+    pub const ORIGIN: Self = PointN([0; N]);
+
+    fn map(&self, f: &dyn Fn(usize) -> isize) -> Self {
+        let mut coordinates = [0; N];
+        for (idx, coordinate) in coordinates.iter_mut().enumerate() {
+            *coordinate = f(idx);
+        }
+        PointN(coordinates)
+    }
+    pub fn componentwise_min(&self, other: &Self) -> Self {
+        self.map(&|idx| self.0[idx].min(other.0[idx]))
+    }
+    pub fn componentwise_max(&self, other: &Self) -> Self {
+        self.map(&|idx| self.0[idx].max(other.0[idx]))
+    }
+    pub fn signum(&self) -> Self {
+        self.map(&|idx| self.0[idx].signum())
+    }
+    pub fn abs(&self) -> Self {
+        self.map(&|idx| self.0[idx].abs())
+    }
+    pub fn dot(&self, other: &Self) -> isize {
+        (0..N).map(|idx| self.0[idx] * other.0[idx]).sum()
+    }
+    pub fn manhattan_norm(&self) -> usize {
+        self.0.iter().map(|c| c.unsigned_abs()).sum()
+    }
+    pub fn chebyshev_norm(&self) -> usize {
+        self.0.iter().map(|c| c.unsigned_abs()).max().unwrap_or(0)
+    }
+}
+
+impl<const N: usize> Add for PointN<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.map(&|idx| self.0[idx] + other.0[idx])
+    }
+}
+
+impl<const N: usize> AddAssign for PointN<N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const N: usize> Sub for PointN<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.map(&|idx| self.0[idx] - other.0[idx])
+    }
+}
+
+impl<const N: usize> SubAssign for PointN<N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const N: usize> Neg for PointN<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(&|idx| -self.0[idx])
+    }
+}
+
+impl<const N: usize> Mul<isize> for PointN<N> {
+    type Output = Self;
+
+    fn mul(self, factor: isize) -> Self {
+        self.map(&|idx| self.0[idx] * factor)
+    }
+}
+
+impl<const N: usize> Div<isize> for PointN<N> {
+    type Output = Self;
+
+    fn div(self, divisor: isize) -> Self {
+        self.map(&|idx| self.0[idx] / divisor)
+    }
+}
+
+impl<const N: usize> FromStr for PointN<N> {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let coordinates = parse_coordinates(value, N)?;
+        Ok(PointN([0; N]).map(&|idx| coordinates[idx]))
+    }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(point: Point) -> Self {
+        PointN([point.x, point.y])
+    }
+}
+
+impl From<PointN<2>> for Point {
+    fn from(point: PointN<2>) -> Self {
+        Point::new(point.0[0], point.0[1])
+    }
+}
+
+impl From<D3Point> for PointN<3> {
+    fn from(point: D3Point) -> Self {
+        PointN([point.x, point.y, point.z])
+    }
+}
+
+impl From<PointN<3>> for D3Point {
+    fn from(point: PointN<3>) -> Self {
+        D3Point {
+            x: point.0[0],
+            y: point.0[1],
+            z: point.0[2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_algebra() {
+        let a = Point::new(3, -4);
+        let b = Point::new(-1, 2);
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(-a, Point::new(-3, 4));
+        assert_eq!(a * 3, Point::new(9, -12));
+        assert_eq!(a / 2, Point::new(1, -2));
+        assert_eq!(a.componentwise_min(&b), Point::new(-1, -4));
+        assert_eq!(a.componentwise_max(&b), Point::new(3, 2));
+        assert_eq!(a.signum(), Point::new(1, -1));
+        assert_eq!(a.abs(), Point::new(3, 4));
+        assert_eq!(a.dot(&b), -11);
+        assert_eq!(a.manhattan_norm(), 7);
+        assert_eq!(a.chebyshev_norm(), 4);
+        let mut c = a;
+        c -= b;
+        c += b;
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn d3_and_n_points_agree() {
+        let a = D3Point { x: 1, y: -5, z: 2 };
+        let b = D3Point { x: 4, y: 0, z: -2 };
+        let (na, nb) = (PointN::from(a), PointN::from(b));
+        assert_eq!(D3Point::from(na - nb), a - b);
+        assert_eq!(D3Point::from(-na * 2 / 3), -a * 2 / 3);
+        assert_eq!(
+            D3Point::from(na.componentwise_min(&nb)),
+            a.componentwise_min(&b)
+        );
+        assert_eq!(D3Point::from((nb - na).signum()), (b - a).signum());
+        assert_eq!(na.dot(&nb), a.dot(&b));
+        assert_eq!(na.manhattan_norm(), 8);
+        assert_eq!(nb.chebyshev_norm(), b.chebyshev_norm());
+        assert_eq!(
+            PointN::<4>::ORIGIN + PointN([1, 2, 3, 4]),
+            PointN([1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "<x=1, y=-2, z=3>".parse(),
+            Ok(D3Point { x: 1, y: -2, z: 3 })
+        );
+        assert_eq!("(4,-5)".parse(), Ok(Point::new(4, -5)));
+        assert_eq!(" < x = 4 , y = 5 > ".parse(), Ok(Point::new(4, 5)));
+        assert_eq!("(1, 2, 3, 4)".parse(), Ok(PointN([1, 2, 3, 4])));
+        assert_eq!(
+            "<x=1, y=2, w=4, z=3>".parse::<PointN<4>>(),
+            Err("unexpected axis w".to_string())
+        );
+        assert_eq!(
+            "(1,2)".parse::<D3Point>(),
+            Err("(1,2) should have 3 coordinates".to_string())
+        );
+        assert_eq!(
+            "1,2".parse::<Point>(),
+            Err("1,2 should be enclosed in () or <>".to_string())
+        );
+        assert_eq!(
+            "(1,a)".parse::<Point>(),
+            Err("bad coordinate a".to_string())
+        );
+    }
+}
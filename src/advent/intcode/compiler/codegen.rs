@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+
+use crate::advent::intcode::compiler::parser::BinaryOperator;
+use crate::advent::intcode::compiler::parser::Expr;
+use crate::advent::intcode::compiler::parser::Function;
+use crate::advent::intcode::compiler::parser::Statement;
+use crate::advent::intcode::compiler::parser::UnaryOperator;
+use crate::advent::intcode::compiler::CompileError;
+use crate::advent::intcode::compiler::Position;
+use crate::advent::intcode::image::Image;
+use crate::advent::intcode::image::Symbol;
+use crate::advent::intcode::instruction::Instruction;
+use crate::advent::intcode::instruction::Mode;
+use crate::advent::intcode::instruction::Opcode;
+
+const BUILTINS: [(&str, usize); 2] = [("input", 0), ("output", 1)];
+
+// Cell of the image, resolved once the addresses or the frame size are known
+#[derive(Clone, Copy, Debug)]
+enum Word {
+    Value(isize),
+    Label(usize),
+    // scale * frame size + offset
+    Frame { scale: isize, offset: isize },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Operand {
+    mode: Mode,
+    word: Word,
+}
+
+// Stack frame, relative to the relative base :
+//   rb+0 return address
+//   rb+1.. parameters, then locals, then temporaries
+// A call writes the callee frame just above the caller frame, the result goes through the RET cell
+struct Generator {
+    code: Vec<Word>,
+    labels: Vec<Option<usize>>,
+    functions: HashMap<String, (usize, usize)>,
+    ret: usize,
+    stack: usize,
+    variables: Vec<HashMap<String, isize>>,
+    slots: isize,
+    temps: isize,
+    max_temps: isize,
+}
+
+impl Operand {
+    fn immediate(value: isize) -> Self {
+        Operand {
+            mode: Mode::Immediate,
+            word: Word::Value(value),
+        }
+    }
+
+    fn label(label: usize) -> Self {
+        Operand {
+            mode: Mode::Immediate,
+            word: Word::Label(label),
+        }
+    }
+
+    fn cell(label: usize) -> Self {
+        Operand {
+            mode: Mode::Position,
+            word: Word::Label(label),
+        }
+    }
+
+    fn slot(offset: isize) -> Self {
+        Operand {
+            mode: Mode::Relative,
+            word: Word::Value(offset),
+        }
+    }
+
+    // slot of the frame of the function being called
+    fn callee_slot(offset: isize) -> Self {
+        Operand {
+            mode: Mode::Relative,
+            word: Word::Frame { scale: 1, offset },
+        }
+    }
+}
+
+pub fn generate(functions: &[Function]) -> Result<Image, CompileError> {
+    let mut generator = Generator {
+        code: Vec::new(),
+        labels: Vec::new(),
+        functions: HashMap::new(),
+        ret: 0,
+        stack: 0,
+        variables: Vec::new(),
+        slots: 0,
+        temps: 0,
+        max_temps: 0,
+    };
+    generator.ret = generator.new_label();
+    generator.stack = generator.new_label();
+
+    for function in functions {
+        if BUILTINS.iter().any(|(name, _)| *name == function.name)
+            || generator.functions.contains_key(&function.name)
+        {
+            return Err(CompileError::new(
+                function.position,
+                format!("function {} is already defined", function.name),
+            ));
+        }
+        let label = generator.new_label();
+        generator
+            .functions
+            .insert(function.name.clone(), (label, function.params.len()));
+    }
+    let main = match functions.iter().find(|f| f.name == "main") {
+        Some(main) if !main.params.is_empty() => {
+            return Err(CompileError::new(
+                main.position,
+                "main can't have parameters".to_string(),
+            ))
+        }
+        Some(_) => generator.functions["main"].0,
+        None => {
+            return Err(CompileError::new(
+                Position { line: 1, column: 1 },
+                "missing main function".to_string(),
+            ))
+        }
+    };
+
+    // entry point : calls main with a frame at the bottom of the stack
+    let halt = generator.new_label();
+    let stack = generator.stack;
+    generator.emit(Opcode::AdjustBase, &[Operand::label(stack)]);
+    generator.emit(
+        Opcode::Add,
+        &[
+            Operand::label(halt),
+            Operand::immediate(0),
+            Operand::slot(0),
+        ],
+    );
+    generator.jump(main);
+    generator.place(halt);
+    generator.emit(Opcode::Halt, &[]);
+
+    let mut symbols = Vec::new();
+    for function in functions {
+        symbols.push((generator.code.len(), function.name.clone()));
+        generator.function(function)?;
+    }
+
+    let ret = generator.ret;
+    symbols.push((generator.code.len(), "RET".to_string()));
+    generator.place(ret);
+    generator.code.push(Word::Value(0));
+    generator.place(stack);
+
+    let mut image = Image::new(generator.resolve());
+    image.protocol = Some("reads with input(), writes with output()".to_string());
+    for (address, name) in symbols {
+        image.symbols.insert(
+            address,
+            Symbol {
+                name,
+                comment: None,
+            },
+        );
+    }
+    Ok(image)
+}
+
+impl Generator {
+    fn new_label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.code.len());
+    }
+
+    fn emit(&mut self, opcode: Opcode, operands: &[Operand]) {
+        let mut modes = [Mode::Position; 3];
+        for (idx, operand) in operands.iter().enumerate() {
+            modes[idx] = operand.mode;
+        }
+        self.code
+            .push(Word::Value(Instruction::new(opcode, modes).encode()));
+        self.code
+            .extend(operands.iter().map(|operand| operand.word));
+    }
+
+    fn jump(&mut self, label: usize) {
+        self.emit(
+            Opcode::JumpIfTrue,
+            &[Operand::immediate(1), Operand::label(label)],
+        );
+    }
+
+    fn copy(&mut self, from: Operand, to: Operand) {
+        self.emit(Opcode::Add, &[from, Operand::immediate(0), to]);
+    }
+
+    fn resolve(&self) -> Vec<isize> {
+        self.code
+            .iter()
+            .map(|word| match *word {
+                Word::Value(value) => value,
+                Word::Label(label) => self.labels[label].unwrap() as isize,
+                Word::Frame { .. } => unreachable!("frame sizes are resolved with the function"),
+            })
+            .collect()
+    }
+
+    fn function(&mut self, function: &Function) -> Result<(), CompileError> {
+        let start = self.code.len();
+        let label = self.functions[&function.name].0;
+        self.place(label);
+        self.variables = vec![HashMap::new()];
+        self.slots = 0;
+        self.temps = 0;
+        self.max_temps = 0;
+        for param in &function.params {
+            self.declare(param, function.position)?;
+        }
+        self.block(&function.body)?;
+        // implicit return 0
+        self.ret(Operand::immediate(0));
+
+        let frame = 1 + self.slots + self.max_temps;
+        for word in &mut self.code[start..] {
+            if let Word::Frame { scale, offset } = *word {
+                *word = Word::Value(scale * frame + offset);
+            }
+        }
+        Ok(())
+    }
+
+    fn declare(&mut self, name: &str, position: Position) -> Result<isize, CompileError> {
+        let scope = self.variables.last_mut().unwrap();
+        if scope.contains_key(name) {
+            return Err(CompileError::new(
+                position,
+                format!("{} is already declared", name),
+            ));
+        }
+        self.slots += 1;
+        scope.insert(name.to_string(), self.slots);
+        Ok(self.slots)
+    }
+
+    fn variable(&self, name: &str, position: Position) -> Result<Operand, CompileError> {
+        self.variables
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .map(|&slot| Operand::slot(slot))
+            .ok_or_else(|| CompileError::new(position, format!("unknown variable {}", name)))
+    }
+
+    // Temporaries live after the locals, their slot is known once the function is compiled
+    fn temp(&mut self) -> Operand {
+        self.temps += 1;
+        self.max_temps = self.max_temps.max(self.temps);
+        Operand {
+            mode: Mode::Relative,
+            word: Word::Frame {
+                scale: 1,
+                offset: -self.temps,
+            },
+        }
+    }
+
+    fn ret(&mut self, value: Operand) {
+        self.copy(value, Operand::cell(self.ret));
+        self.emit(
+            Opcode::JumpIfTrue,
+            &[Operand::immediate(1), Operand::slot(0)],
+        );
+    }
+
+    fn block(&mut self, statements: &[Statement]) -> Result<(), CompileError> {
+        self.variables.push(HashMap::new());
+        for statement in statements {
+            self.statement(statement)?;
+        }
+        self.variables.pop();
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<(), CompileError> {
+        match statement {
+            Statement::Let(name, value, position) => {
+                let value = self.expr(value)?;
+                let slot = self.declare(name, *position)?;
+                self.copy(value, Operand::slot(slot));
+            }
+            Statement::Assign(name, value, position) => {
+                let slot = self.variable(name, *position)?;
+                let value = self.expr(value)?;
+                self.copy(value, slot);
+            }
+            Statement::If(condition, then, otherwise) => {
+                let (otherwise_label, end) = (self.new_label(), self.new_label());
+                let condition = self.expr(condition)?;
+                self.emit(
+                    Opcode::JumpIfFalse,
+                    &[condition, Operand::label(otherwise_label)],
+                );
+                self.block(then)?;
+                self.jump(end);
+                self.place(otherwise_label);
+                self.block(otherwise)?;
+                self.place(end);
+            }
+            Statement::While(condition, body) => {
+                let (start, end) = (self.new_label(), self.new_label());
+                self.place(start);
+                let condition = self.expr(condition)?;
+                self.emit(Opcode::JumpIfFalse, &[condition, Operand::label(end)]);
+                self.block(body)?;
+                self.jump(start);
+                self.place(end);
+            }
+            Statement::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expr(value)?,
+                    None => Operand::immediate(0),
+                };
+                self.ret(value);
+            }
+            Statement::Expr(expr) => {
+                self.expr(expr)?;
+            }
+        }
+        self.temps = 0;
+        Ok(())
+    }
+
+    // The operand holding the value of the expression, valid until the end of the statement
+    fn expr(&mut self, expr: &Expr) -> Result<Operand, CompileError> {
+        match expr {
+            Expr::Number(value) => Ok(Operand::immediate(*value)),
+            Expr::Variable(name, position) => self.variable(name, *position),
+            Expr::Call(name, args, position) => self.call(name, args, *position),
+            Expr::Unary(operator, operand) => {
+                let mark = self.temps;
+                let operand = self.expr(operand)?;
+                self.temps = mark;
+                let result = self.temp();
+                match operator {
+                    UnaryOperator::Neg => {
+                        self.emit(Opcode::Mul, &[operand, Operand::immediate(-1), result])
+                    }
+                    UnaryOperator::Not => {
+                        self.emit(Opcode::Equals, &[operand, Operand::immediate(0), result])
+                    }
+                }
+                Ok(result)
+            }
+            Expr::Binary(operator @ (BinaryOperator::And | BinaryOperator::Or), left, right) => {
+                // short circuit : the right side is only evaluated when needed
+                let and = *operator == BinaryOperator::And;
+                let mark = self.temps;
+                let result = self.temp();
+                let end = self.new_label();
+                self.copy(Operand::immediate(!and as isize), result);
+                let left = self.expr(left)?;
+                let skip = if and {
+                    Opcode::JumpIfFalse
+                } else {
+                    Opcode::JumpIfTrue
+                };
+                self.emit(skip, &[left, Operand::label(end)]);
+                let right = self.expr(right)?;
+                self.emit(Opcode::Equals, &[right, Operand::immediate(0), result]);
+                self.emit(Opcode::Equals, &[result, Operand::immediate(0), result]);
+                self.place(end);
+                self.temps = mark + 1;
+                Ok(result)
+            }
+            Expr::Binary(operator, left, right) => {
+                let mark = self.temps;
+                let left = self.expr(left)?;
+                let right = self.expr(right)?;
+                self.binary(*operator, left, right, mark)
+            }
+        }
+    }
+
+    fn binary(
+        &mut self,
+        operator: BinaryOperator,
+        left: Operand,
+        right: Operand,
+        mark: isize,
+    ) -> Result<Operand, CompileError> {
+        let (opcode, left, right, negate) = match operator {
+            BinaryOperator::Add => (Opcode::Add, left, right, false),
+            BinaryOperator::Mul => (Opcode::Mul, left, right, false),
+            BinaryOperator::Lt => (Opcode::LessThan, left, right, false),
+            BinaryOperator::Gt => (Opcode::LessThan, right, left, false),
+            BinaryOperator::Ge => (Opcode::LessThan, left, right, true),
+            BinaryOperator::Le => (Opcode::LessThan, right, left, true),
+            BinaryOperator::Eq => (Opcode::Equals, left, right, false),
+            BinaryOperator::Ne => (Opcode::Equals, left, right, true),
+            BinaryOperator::Sub => {
+                let negated = self.temp();
+                self.emit(Opcode::Mul, &[right, Operand::immediate(-1), negated]);
+                (Opcode::Add, left, negated, false)
+            }
+            BinaryOperator::And | BinaryOperator::Or => unreachable!("short circuit operators"),
+        };
+        // the operands are read before the result is written : their temporaries can be reused
+        self.temps = mark;
+        let result = self.temp();
+        self.emit(opcode, &[left, right, result]);
+        if negate {
+            self.emit(Opcode::Equals, &[result, Operand::immediate(0), result]);
+        }
+        Ok(result)
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        args: &[Expr],
+        position: Position,
+    ) -> Result<Operand, CompileError> {
+        let (label, arity) = match BUILTINS.iter().find(|(builtin, _)| *builtin == name) {
+            Some(&(_, arity)) => (None, arity),
+            None => match self.functions.get(name) {
+                Some(&(label, arity)) => (Some(label), arity),
+                None => {
+                    return Err(CompileError::new(
+                        position,
+                        format!("unknown function {}", name),
+                    ))
+                }
+            },
+        };
+        if args.len() != arity {
+            return Err(CompileError::new(
+                position,
+                format!("{} expects {} argument(s), got {}", name, arity, args.len()),
+            ));
+        }
+
+        // arguments are evaluated first : a nested call would overwrite the callee frame
+        let mark = self.temps;
+        let mut values = Vec::new();
+        for arg in args {
+            let value = self.expr(arg)?;
+            let held = self.temp();
+            self.copy(value, held);
+            values.push(held);
+        }
+        let result = match label {
+            None if name == "input" => {
+                let result = self.temp();
+                self.emit(Opcode::Input, &[result]);
+                result
+            }
+            None => {
+                self.emit(Opcode::Output, &[values[0]]);
+                Operand::immediate(0)
+            }
+            Some(label) => {
+                let back = self.new_label();
+                self.copy(Operand::label(back), Operand::callee_slot(0));
+                for (idx, &value) in values.iter().enumerate() {
+                    self.copy(value, Operand::callee_slot(idx as isize + 1));
+                }
+                self.emit(
+                    Opcode::AdjustBase,
+                    &[Operand {
+                        mode: Mode::Immediate,
+                        word: Word::Frame {
+                            scale: 1,
+                            offset: 0,
+                        },
+                    }],
+                );
+                self.jump(label);
+                self.place(back);
+                self.emit(
+                    Opcode::AdjustBase,
+                    &[Operand {
+                        mode: Mode::Immediate,
+                        word: Word::Frame {
+                            scale: -1,
+                            offset: 0,
+                        },
+                    }],
+                );
+                self.temps = mark;
+                let result = self.temp();
+                self.copy(Operand::cell(self.ret), result);
+                result
+            }
+        };
+        Ok(result)
+    }
+}
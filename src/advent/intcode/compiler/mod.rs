@@ -0,0 +1,187 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+
+use crate::advent::intcode::image::Image;
+
+mod codegen;
+mod parser;
+
+// Tiny structured language compiled to Intcode :
+//   fn square(x) { return x * x; }
+//   fn main() {
+//       let n = input();
+//       while n > 0 {
+//           output(square(n));
+//           n = n - 1;
+//       }
+//   }
+// Values are integers, conditions are true when not 0. Operators : || && == != < <= > >= + - * !
+// Functions use the relative base as a stack, execution starts with main
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileError {
+    pub position: Position,
+    pub message: String,
+}
+
+pub fn compile(source: &str) -> Result<Image, CompileError> {
+    codegen::generate(&parser::parse(source)?)
+}
+
+impl CompileError {
+    pub fn new(position: Position, message: String) -> Self {
+        CompileError { position, message }
+    }
+
+    // The message followed by the faulty line, with a caret under the error
+    pub fn render(&self, source: &str) -> String {
+        let line = source.lines().nth(self.position.line - 1).unwrap_or("");
+        format!(
+            "{}\n{}\n{}^",
+            self,
+            line,
+            " ".repeat(self.position.column - 1)
+        )
+    }
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "line {}, column {} : {}",
+            self.position.line, self.position.column, self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::Program;
+
+    fn run(source: &str, inputs: Vec<isize>) -> Vec<isize> {
+        let mut program = compile(source).unwrap().to_program();
+        let outputs = program.exchange(inputs);
+        assert!(program.state.is_halted(), "{}", program.state);
+        outputs
+    }
+
+    fn error(source: &str) -> String {
+        compile(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn loops_and_arithmetic() {
+        let source = "
+            fn square(x) { return x * x; }
+            fn main() {
+                let n = input();
+                while n > 0 {
+                    output(square(n) - 1);
+                    n = n - 1;
+                }
+            }";
+        assert_eq!(run(source, vec![4]), vec![15, 8, 3, 0]);
+    }
+
+    #[test]
+    fn recursion_uses_the_stack() {
+        let source = "
+            fn fibonacci(n) {
+                if n < 2 {
+                    return n;
+                }
+                return fibonacci(n - 1) + fibonacci(n - 2);
+            }
+            fn main() {
+                output(fibonacci(input()));
+            }";
+        assert_eq!(run(source, vec![15]), vec![610]);
+    }
+
+    #[test]
+    fn conditions() {
+        let source = "
+            fn sign(x) {
+                if x < 0 { return -1; } else if x == 0 { return 0; } else { return 1; }
+            }
+            fn main() {
+                let a = input();
+                let b = input();
+                output(sign(a));
+                output(a <= b);
+                output(a >= b);
+                output(a != b && !(a > b));
+                // input() is not evaluated
+                output(a == a || input());
+                output(-a * (b + 2));
+            }";
+        assert_eq!(run(source, vec![-3, 4]), vec![-1, 1, 0, 1, 1, 18]);
+        assert_eq!(run(source, vec![4, 4]), vec![1, 1, 1, 0, 1, -24]);
+    }
+
+    #[test]
+    fn scopes_and_arguments() {
+        let source = "
+            fn weighted(a, b, c) { return a + 10 * b + 100 * c; }
+            fn main() {
+                let x = 1;
+                if x {
+                    let x = 2;
+                    output(x);
+                }
+                output(weighted(x, weighted(1, 2, 3), 3));
+            }";
+        assert_eq!(run(source, vec![]), vec![2, 3511]);
+    }
+
+    #[test]
+    fn image_has_symbols() {
+        let image = compile("fn main() { output(42); }").unwrap();
+        assert!(image.address_of("main").is_some());
+        assert!(image.address_of("RET").is_some());
+        assert_eq!(Program::new(image.memory).exchange(vec![]), vec![42]);
+    }
+
+    #[test]
+    fn readable_errors() {
+        assert_eq!(
+            error("fn main() {\n  let a = 1;\n  output(b);\n}"),
+            "line 3, column 10 : unknown variable b"
+        );
+        assert_eq!(
+            error("fn main() { output(1, 2); }"),
+            "line 1, column 13 : output expects 1 argument(s), got 2"
+        );
+        assert_eq!(
+            error("fn main() { let a = 1 }"),
+            "line 1, column 23 : expected ';', found '}'"
+        );
+        assert_eq!(
+            error("fn main() { foo(); }"),
+            "line 1, column 13 : unknown function foo"
+        );
+        assert_eq!(
+            error("fn f() {}"),
+            "line 1, column 1 : missing main function"
+        );
+        assert_eq!(
+            error("fn main() { let a = 3 / 2; }"),
+            "line 1, column 23 : unexpected character '/'"
+        );
+        assert_eq!(
+            compile("fn main() {\n  x = 1;\n}")
+                .unwrap_err()
+                .render("fn main() {\n  x = 1;\n}"),
+            "line 2, column 3 : unknown variable x\n  x = 1;\n  ^"
+        );
+    }
+}
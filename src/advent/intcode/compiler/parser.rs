@@ -0,0 +1,372 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+
+use crate::advent::intcode::compiler::CompileError;
+use crate::advent::intcode::compiler::Position;
+
+const KEYWORDS: [&str; 6] = ["fn", "let", "if", "else", "while", "return"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(isize),
+    Identifier(String),
+    Keyword(&'static str),
+    Symbol(&'static str),
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOperator {
+    Neg,
+    Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Number(isize),
+    Variable(String, Position),
+    Call(String, Vec<Expr>, Position),
+    Unary(UnaryOperator, Box<Expr>),
+    Binary(BinaryOperator, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Statement {
+    Let(String, Expr, Position),
+    Assign(String, Expr, Position),
+    If(Expr, Vec<Statement>, Vec<Statement>),
+    While(Expr, Vec<Statement>),
+    Return(Option<Expr>),
+    Expr(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Statement>,
+    pub position: Position,
+}
+
+// Binary operators by increasing precedence
+const PRECEDENCE: [&[(&str, BinaryOperator)]; 5] = [
+    &[("||", BinaryOperator::Or)],
+    &[("&&", BinaryOperator::And)],
+    &[("==", BinaryOperator::Eq), ("!=", BinaryOperator::Ne)],
+    &[
+        ("<=", BinaryOperator::Le),
+        (">=", BinaryOperator::Ge),
+        ("<", BinaryOperator::Lt),
+        (">", BinaryOperator::Gt),
+    ],
+    &[("+", BinaryOperator::Add), ("-", BinaryOperator::Sub)],
+];
+
+// Longest symbols first
+const SYMBOLS: [&str; 19] = [
+    "==", "!=", "<=", ">=", "&&", "||", "(", ")", "{", "}", ",", ";", "=", "<", ">", "+", "-", "*",
+    "!",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, CompileError> {
+    let mut tokens = Vec::new();
+    for (line_idx, line) in source.lines().enumerate() {
+        let line = line.split("//").next().unwrap();
+        let chars: Vec<char> = line.chars().collect();
+        let mut column = 0;
+        while column < chars.len() {
+            let position = Position {
+                line: line_idx + 1,
+                column: column + 1,
+            };
+            let c = chars[column];
+            if c.is_whitespace() {
+                column += 1;
+            } else if c.is_ascii_digit() || c.is_alphabetic() || c == '_' {
+                let word: String = chars[column..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .collect();
+                column += word.chars().count();
+                let token = if c.is_ascii_digit() {
+                    Token::Number(word.parse().map_err(|_| {
+                        CompileError::new(position, format!("invalid number {}", word))
+                    })?)
+                } else if let Some(keyword) = KEYWORDS.iter().find(|&&k| k == word) {
+                    Token::Keyword(keyword)
+                } else {
+                    Token::Identifier(word)
+                };
+                tokens.push((token, position));
+            } else {
+                let rest: String = chars[column..].iter().collect();
+                let symbol = SYMBOLS
+                    .iter()
+                    .find(|&&s| rest.starts_with(s))
+                    .ok_or_else(|| {
+                        CompileError::new(position, format!("unexpected character '{}'", c))
+                    })?;
+                column += symbol.len();
+                tokens.push((Token::Symbol(symbol), position));
+            }
+        }
+    }
+    let end = Position {
+        line: source.lines().count().max(1),
+        column: source.lines().last().map_or(0, |l| l.chars().count()) + 1,
+    };
+    tokens.push((Token::End, end));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    idx: usize,
+}
+
+pub fn parse(source: &str) -> Result<Vec<Function>, CompileError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        idx: 0,
+    };
+    let mut functions = Vec::new();
+    while parser.peek() != &Token::End {
+        functions.push(parser.function()?);
+    }
+    Ok(functions)
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::Keyword(keyword) => write!(f, "{}", keyword),
+            Token::Symbol(symbol) => write!(f, "'{}'", symbol),
+            Token::End => write!(f, "end of file"),
+        }
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.idx].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.idx].1
+    }
+
+    fn accept(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Token::Symbol(s) | Token::Keyword(s) if *s == symbol => {
+                self.idx += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), CompileError> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", symbol)))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> CompileError {
+        CompileError::new(
+            self.position(),
+            format!("expected {}, found {}", expected, self.peek()),
+        )
+    }
+
+    fn identifier(&mut self) -> Result<(String, Position), CompileError> {
+        match self.peek().clone() {
+            Token::Identifier(name) => {
+                let position = self.position();
+                self.idx += 1;
+                Ok((name, position))
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn function(&mut self) -> Result<Function, CompileError> {
+        let position = self.position();
+        self.expect("fn")?;
+        let (name, _) = self.identifier()?;
+        self.expect("(")?;
+        let mut params = Vec::new();
+        if !self.accept(")") {
+            loop {
+                params.push(self.identifier()?.0);
+                if self.accept(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        Ok(Function {
+            name,
+            params,
+            body: self.block()?,
+            position,
+        })
+    }
+
+    fn block(&mut self) -> Result<Vec<Statement>, CompileError> {
+        self.expect("{")?;
+        let mut statements = Vec::new();
+        while !self.accept("}") {
+            if self.peek() == &Token::End {
+                return Err(self.unexpected("'}'"));
+            }
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Statement, CompileError> {
+        if self.accept("let") {
+            let (name, position) = self.identifier()?;
+            self.expect("=")?;
+            let value = self.expr()?;
+            self.expect(";")?;
+            Ok(Statement::Let(name, value, position))
+        } else if self.accept("if") {
+            self.if_statement()
+        } else if self.accept("while") {
+            let condition = self.expr()?;
+            Ok(Statement::While(condition, self.block()?))
+        } else if self.accept("return") {
+            let value = if self.accept(";") {
+                None
+            } else {
+                let value = self.expr()?;
+                self.expect(";")?;
+                Some(value)
+            };
+            Ok(Statement::Return(value))
+        } else if matches!(self.peek(), Token::Identifier(_))
+            && self.tokens[self.idx + 1].0 == Token::Symbol("=")
+        {
+            let (name, position) = self.identifier()?;
+            self.expect("=")?;
+            let value = self.expr()?;
+            self.expect(";")?;
+            Ok(Statement::Assign(name, value, position))
+        } else {
+            let expr = self.expr()?;
+            self.expect(";")?;
+            Ok(Statement::Expr(expr))
+        }
+    }
+
+    // "if" already consumed
+    fn if_statement(&mut self) -> Result<Statement, CompileError> {
+        let condition = self.expr()?;
+        let then = self.block()?;
+        let otherwise = if !self.accept("else") {
+            Vec::new()
+        } else if self.accept("if") {
+            vec![self.if_statement()?]
+        } else {
+            self.block()?
+        };
+        Ok(Statement::If(condition, then, otherwise))
+    }
+
+    fn expr(&mut self) -> Result<Expr, CompileError> {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, CompileError> {
+        if level == PRECEDENCE.len() {
+            return self.product();
+        }
+        let mut left = self.binary(level + 1)?;
+        'operators: loop {
+            for (symbol, operator) in PRECEDENCE[level] {
+                if self.accept(symbol) {
+                    let right = self.binary(level + 1)?;
+                    left = Expr::Binary(*operator, Box::new(left), Box::new(right));
+                    continue 'operators;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, CompileError> {
+        let mut left = self.unary()?;
+        while self.accept("*") {
+            left = Expr::Binary(BinaryOperator::Mul, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        if self.accept("-") {
+            Ok(match self.unary()? {
+                Expr::Number(value) => Expr::Number(-value),
+                expr => Expr::Unary(UnaryOperator::Neg, Box::new(expr)),
+            })
+        } else if self.accept("!") {
+            Ok(Expr::Unary(UnaryOperator::Not, Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        let position = self.position();
+        match self.peek().clone() {
+            Token::Number(value) => {
+                self.idx += 1;
+                Ok(Expr::Number(value))
+            }
+            Token::Identifier(name) => {
+                self.idx += 1;
+                if !self.accept("(") {
+                    return Ok(Expr::Variable(name, position));
+                }
+                let mut args = Vec::new();
+                if !self.accept(")") {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.accept(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Expr::Call(name, args, position))
+            }
+            Token::Symbol("(") => {
+                self.idx += 1;
+                let expr = self.expr()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crate::advent::intcode::Program;
+
+pub const CORPUS: &str = "src/advent/intcode/corpus";
+
+// Any Intcode implementation which can be checked against the corpus
+pub trait Vm {
+    fn load(image: &[isize]) -> Self;
+    // Runs until the program halts or waits for more inputs
+    fn run(&mut self, inputs: &[isize]) -> Vec<isize>;
+    fn halted(&self) -> bool;
+}
+
+impl Vm for Program {
+    fn load(image: &[isize]) -> Self {
+        Program::new(image.to_vec())
+    }
+
+    fn run(&mut self, inputs: &[isize]) -> Vec<isize> {
+        self.exchange(inputs.iter().cloned())
+    }
+
+    fn halted(&self) -> bool {
+        self.state.is_halted()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    pub inputs: Vec<isize>,
+    pub outputs: Vec<isize>,
+    // false when the program is expected to wait for more inputs
+    pub halts: bool,
+}
+
+// Corpus file :
+//   # description
+//   program = 3,9,8,9,10,9,4,9,99,-1,8
+//   run = 8 -> 1
+//   wait = 5,0 -> 1
+// Every run starts from a freshly loaded program
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Case {
+    pub name: String,
+    pub program: Vec<isize>,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub case: String,
+    pub run: usize,
+    pub expected: Run,
+    pub actual: Run,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "{} run #{} : expected {:?} (halts : {}), got {:?} (halts : {})",
+            self.case,
+            self.run,
+            self.expected.outputs,
+            self.expected.halts,
+            self.actual.outputs,
+            self.actual.halts
+        )
+    }
+}
+
+impl FromStr for Case {
+    type Err = String;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut program = None;
+        let mut runs = Vec::new();
+        for (idx, line) in content.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {} : {}", idx + 1, message);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error(format!("bad line {}", line)))?;
+            match key.trim() {
+                "program" => program = Some(parse_values(value).map_err(error)?),
+                kind @ ("run" | "wait") => {
+                    let (inputs, outputs) = value
+                        .split_once("->")
+                        .ok_or_else(|| error(format!("missing -> in {}", line)))?;
+                    runs.push(Run {
+                        inputs: parse_values(inputs).map_err(error)?,
+                        outputs: parse_values(outputs).map_err(error)?,
+                        halts: kind == "run",
+                    });
+                }
+                key => return Err(error(format!("unknown key {}", key))),
+            }
+        }
+        Ok(Case {
+            name: String::new(),
+            program: program.ok_or("missing program")?,
+            runs,
+        })
+    }
+}
+
+fn parse_values(values: &str) -> Result<Vec<isize>, String> {
+    values
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.parse().map_err(|_| format!("bad value {}", v)))
+        .collect()
+}
+
+// Cases sorted by file name
+pub fn load_corpus(directory: &str) -> Result<Vec<Case>, String> {
+    let mut files: Vec<_> = std::fs::read_dir(directory)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    files.sort();
+    files
+        .iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let mut case: Case = crate::read_file(path.to_str().unwrap())
+                .parse()
+                .map_err(|e| format!("{} : {}", name, e))?;
+            case.name = name;
+            Ok(case)
+        })
+        .collect()
+}
+
+pub fn check<V: Vm>(case: &Case) -> Vec<Mismatch> {
+    case.runs
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, expected)| {
+            let mut vm = V::load(&case.program);
+            let actual = Run {
+                inputs: expected.inputs.clone(),
+                outputs: vm.run(&expected.inputs),
+                halts: vm.halted(),
+            };
+            if actual != *expected {
+                Some(Mismatch {
+                    case: case.name.clone(),
+                    run: idx,
+                    expected: expected.clone(),
+                    actual,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub fn check_corpus<V: Vm>(cases: &[Case]) -> Vec<Mismatch> {
+    cases.iter().flat_map(check::<V>).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Forgets its last output
+    struct Lossy(Program);
+
+    impl Vm for Lossy {
+        fn load(image: &[isize]) -> Self {
+            Lossy(Program::load(image))
+        }
+
+        fn run(&mut self, inputs: &[isize]) -> Vec<isize> {
+            let mut outputs = self.0.exchange(inputs.to_vec());
+            outputs.pop();
+            outputs
+        }
+
+        fn halted(&self) -> bool {
+            self.0.halted()
+        }
+    }
+
+    #[test]
+    fn program_passes_the_corpus() {
+        let cases = load_corpus(CORPUS).unwrap();
+        assert!(cases.len() >= 16);
+        let mismatches: Vec<String> = check_corpus::<Program>(&cases)
+            .iter()
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(mismatches, Vec::<String>::new());
+    }
+
+    #[test]
+    fn report_mismatches() {
+        let mut case: Case = "program = 104,1,104,2,99\nrun = -> 1,2".parse().unwrap();
+        case.name = "two".to_string();
+        assert!(check::<Program>(&case).is_empty());
+        assert_eq!(
+            check::<Lossy>(&case)
+                .iter()
+                .map(|m| m.to_string())
+                .collect::<Vec<String>>(),
+            vec!["two run #0 : expected [1, 2] (halts : true), got [1] (halts : true)"]
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_the_line() {
+        assert_eq!(
+            "program = 99\nrun = 1".parse::<Case>(),
+            Err("line 2 : missing -> in run = 1".to_string())
+        );
+        assert_eq!(
+            "run = ->".parse::<Case>(),
+            Err("missing program".to_string())
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+
+use crate::advent::intcode::disasm::decode_line;
+use crate::advent::intcode::disasm::format_line;
+use crate::advent::intcode::disasm::Line;
+use crate::advent::intcode::image::Image;
+
+// From cold to hot
+const HEAT: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+// Accesses counted by address, over one or more runs
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    // first cell of the executed instructions
+    pub executed: BTreeMap<usize, usize>,
+    // every cell of the executed instructions, parameters included
+    pub code: BTreeMap<usize, usize>,
+    // cells read or written through position and relative parameters
+    pub read: BTreeMap<usize, usize>,
+    pub written: BTreeMap<usize, usize>,
+}
+
+impl Coverage {
+    pub fn record_execution(&mut self, address: usize, size: usize) {
+        *self.executed.entry(address).or_insert(0) += 1;
+        for cell in address..address + size {
+            *self.code.entry(cell).or_insert(0) += 1;
+        }
+    }
+
+    pub fn record_read(&mut self, address: usize) {
+        *self.read.entry(address).or_insert(0) += 1;
+    }
+
+    pub fn record_write(&mut self, address: usize) {
+        *self.written.entry(address).or_insert(0) += 1;
+    }
+
+    pub fn merge(&mut self, other: &Coverage) {
+        for (counts, others) in [
+            (&mut self.executed, &other.executed),
+            (&mut self.code, &other.code),
+            (&mut self.read, &other.read),
+            (&mut self.written, &other.written),
+        ] {
+            for (&address, &count) in others {
+                *counts.entry(address).or_insert(0) += count;
+            }
+        }
+    }
+
+    // Read or written, never executed
+    pub fn is_data(&self, address: usize) -> bool {
+        !self.code.contains_key(&address)
+            && (self.read.contains_key(&address) || self.written.contains_key(&address))
+    }
+
+    // Part of the image executed as instructions, in percent
+    pub fn ratio(&self, image_len: usize) -> f64 {
+        let covered = self.code.range(..image_len).count();
+        100. * covered as f64 / image_len.max(1) as f64
+    }
+
+    // Disassembly driven by the coverage : only executed instructions are decoded.
+    // Each line starts with the execution count and X (executed) R (read) W (written) flags
+    pub fn listing(&self, image: &Image) -> String {
+        let mut lines = Vec::new();
+        let mut address = 0;
+        while address < image.memory.len() {
+            let line = match self.executed.get(&address) {
+                Some(_) => decode_line(image, address),
+                None => Line {
+                    address,
+                    size: 1,
+                    instruction: None,
+                    text: format!("DATA {}", image.memory[address]),
+                },
+            };
+            let cells = line.address..line.address + line.size;
+            let flag = |set: &dyn Fn(usize) -> bool, flag| {
+                if cells.clone().any(set) {
+                    flag
+                } else {
+                    '-'
+                }
+            };
+            lines.push(format!(
+                "{:>8} {}{}{} {}",
+                self.executed
+                    .get(&address)
+                    .map_or(String::new(), |c| c.to_string()),
+                flag(&|a| self.code.contains_key(&a), 'X'),
+                flag(&|a| self.read.contains_key(&a), 'R'),
+                flag(&|a| self.written.contains_key(&a), 'W'),
+                format_line(image, &line)
+            ));
+            address += line.size;
+        }
+        lines.join("\n")
+    }
+
+    // One character per cell, width cells per row : the hotter the cell, the denser the character
+    pub fn heatmap(&self, image_len: usize, width: usize) -> String {
+        assert!(width > 0, "heatmap width must be non-zero");
+        let count = |address: usize| {
+            self.code.get(&address).unwrap_or(&0)
+                + self.read.get(&address).unwrap_or(&0)
+                + self.written.get(&address).unwrap_or(&0)
+        };
+        let max = (0..image_len).map(count).max().unwrap_or(0).max(1) as f64;
+        (0..image_len)
+            .map(|address| match count(address) {
+                0 => HEAT[0],
+                c => HEAT[1 + ((c as f64).ln_1p() / max.ln_1p() * 8.).round() as usize],
+            })
+            .collect::<Vec<char>>()
+            .chunks(width)
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::parse_input;
+    use crate::advent::intcode::Program;
+
+    fn covered(image: &[isize], inputs: &[isize]) -> Coverage {
+        let mut program = Program::new(image.to_vec());
+        program.record_coverage();
+        program.input.extend(inputs);
+        program.execute();
+        program.coverage.unwrap()
+    }
+
+    #[test]
+    fn tell_code_from_data() {
+        // doubles its input, skipping the data cell at 3
+        let image = [1105, 1, 4, 7, 3, 3, 1002, 3, 2, 3, 4, 3, 99];
+        let coverage = covered(&image, &[21]);
+        assert_eq!(
+            coverage.executed.keys().cloned().collect::<Vec<usize>>(),
+            vec![0, 4, 6, 10, 12]
+        );
+        assert!(coverage.is_data(3));
+        assert!(!coverage.is_data(4));
+        assert_eq!(coverage.read[&3], 2);
+        assert_eq!(coverage.written[&3], 2);
+        assert_eq!(
+            coverage.listing(&Image::new(image.to_vec())),
+            [
+                "       1 X--      0               JT 1, 4",
+                "         -RW      3               DATA 7",
+                "       1 X--      4               IN [3]",
+                "       1 X--      6               MUL [3], 2, [3]",
+                "       1 X--     10               OUT [3]",
+                "       1 X--     12               HALT",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn merge_runs_and_render_heatmap() {
+        // the loop only runs when the input is not 0
+        let image = [
+            3, 20, 1006, 20, 16, 1001, 20, -1, 20, 104, 1, 1105, 1, 2, 0, 0, 99,
+        ];
+        let mut coverage = covered(&image, &[0]);
+        let short = coverage.ratio(image.len());
+        coverage.merge(&covered(&image, &[3]));
+        assert!(coverage.ratio(image.len()) > short);
+        assert_eq!(coverage.ratio(image.len()), 100. * 15. / 17.);
+        assert_eq!(coverage.executed[&2], 5);
+        assert_eq!(coverage.heatmap(image.len(), 8), "**@@@###\n######\n*");
+    }
+
+    #[test]
+    #[should_panic(expected = "heatmap width must be non-zero")]
+    fn heatmap_needs_a_width() {
+        covered(&[104, 1, 99], &[]).heatmap(3, 0);
+    }
+
+    #[test]
+    fn puzzle_program_coverage() {
+        // the diagnostic for the ship's air conditioner doesn't run the thermal radiator code
+        let image = parse_input("05");
+        let mut coverage = covered(&image, &[1]);
+        let air_conditioner = coverage.ratio(image.len());
+        coverage.merge(&covered(&image, &[5]));
+        assert!(coverage.ratio(image.len()) > air_conditioner);
+        assert!(coverage
+            .code
+            .keys()
+            .all(|&address| !coverage.is_data(address)));
+    }
+}
@@ -0,0 +1,820 @@
+use std::collections::HashSet;
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+
+use crate::advent::intcode::disasm::disassemble;
+use crate::advent::intcode::disasm::listing;
+use crate::advent::intcode::disasm::Line;
+use crate::advent::intcode::image::Image;
+use crate::advent::intcode::json::Json;
+use crate::advent::intcode::Program;
+use crate::advent::intcode::ProgramState;
+
+const THREAD: isize = 1;
+// the program is shown as its disassembly, a source without file
+const SOURCE: isize = 1;
+const REGISTERS: isize = 1;
+const MEMORY: isize = 2;
+const IO: isize = 3;
+// memory cells shown from the instruction pointer
+const MEMORY_WINDOW: usize = 16;
+// larger messages are skipped without being allocated
+const MAX_CONTENT_LENGTH: usize = 1 << 20;
+// steps run by a continue before pausing, so that the client gets a hand back on endless programs
+const CONTINUE_BUDGET: usize = 1_000_000;
+
+// Debug adapter protocol session for a single program, run synchronously between requests
+#[derive(Default)]
+pub struct Session {
+    seq: isize,
+    image: Image,
+    program: Option<Program>,
+    lines: Vec<Line>,
+    source_breakpoints: HashSet<usize>,
+    instruction_breakpoints: HashSet<usize>,
+    stop_on_entry: bool,
+    continue_budget: usize,
+    pub finished: bool,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session::default()
+    }
+
+    // The response to the request followed by the events it triggered
+    pub fn handle(&mut self, request: &Json) -> Vec<Json> {
+        let command = request.get("command").as_str().unwrap_or("").to_string();
+        let mut events = Vec::new();
+        let result = self.command(&command, request.get("arguments"), &mut events);
+        let mut messages = vec![self.response(request.get("seq").clone(), command, result)];
+        for (event, body) in events {
+            let event = vec![
+                ("type", Json::from("event")),
+                ("event", Json::from(event)),
+                ("body", body),
+            ];
+            messages.push(self.message(event));
+        }
+        messages
+    }
+
+    // Failed response to a message which isn't a valid request
+    pub fn reject(&mut self, message: String) -> Json {
+        self.response(Json::Number(0), String::new(), Err(message))
+    }
+
+    fn response(
+        &mut self,
+        request_seq: Json,
+        command: String,
+        result: Result<Json, String>,
+    ) -> Json {
+        let mut response = vec![
+            ("type", Json::from("response")),
+            ("request_seq", request_seq),
+            ("command", Json::from(command)),
+            ("success", Json::from(result.is_ok())),
+        ];
+        match result {
+            Ok(body) => response.push(("body", body)),
+            Err(message) => response.push(("message", Json::from(message))),
+        }
+        self.message(response)
+    }
+
+    fn message(&mut self, mut fields: Vec<(&str, Json)>) -> Json {
+        self.seq += 1;
+        fields.push(("seq", Json::from(self.seq)));
+        Json::object(fields)
+    }
+
+    fn command(
+        &mut self,
+        command: &str,
+        args: &Json,
+        events: &mut Vec<(&'static str, Json)>,
+    ) -> Result<Json, String> {
+        match command {
+            "initialize" => {
+                events.push(("initialized", Json::object(vec![])));
+                Ok(Json::object(vec![
+                    ("supportsConfigurationDoneRequest", Json::from(true)),
+                    ("supportsInstructionBreakpoints", Json::from(true)),
+                    ("supportsDisassembleRequest", Json::from(true)),
+                    ("supportsSteppingGranularity", Json::from(true)),
+                ]))
+            }
+            "launch" => self.launch(args),
+            "setBreakpoints" => self.set_breakpoints(args),
+            "setInstructionBreakpoints" => self.set_instruction_breakpoints(args),
+            "setExceptionBreakpoints" => Ok(Json::object(vec![])),
+            "configurationDone" => {
+                if self.stop_on_entry {
+                    events.push(self.stopped("entry", None));
+                } else {
+                    self.resume(None, events)?;
+                }
+                Ok(Json::object(vec![]))
+            }
+            "threads" => Ok(Json::object(vec![(
+                "threads",
+                Json::from(vec![Json::object(vec![
+                    ("id", Json::from(THREAD)),
+                    ("name", Json::from("intcode")),
+                ])]),
+            )])),
+            "continue" => {
+                self.resume(None, events)?;
+                Ok(Json::object(vec![(
+                    "allThreadsContinued",
+                    Json::from(true),
+                )]))
+            }
+            // no call stack to step in or out of : only next is supported
+            "next" => {
+                self.resume(Some(1), events)?;
+                Ok(Json::object(vec![]))
+            }
+            // requests are only read while the program is stopped : it is already paused
+            "pause" => Ok(Json::object(vec![])),
+            "stackTrace" => self.stack_trace(),
+            "scopes" => Ok(Json::object(vec![(
+                "scopes",
+                Json::from(
+                    [("Registers", REGISTERS), ("Memory", MEMORY), ("I/O", IO)]
+                        .iter()
+                        .map(|&(name, reference)| {
+                            Json::object(vec![
+                                ("name", Json::from(name)),
+                                ("variablesReference", Json::from(reference)),
+                                ("expensive", Json::from(false)),
+                            ])
+                        })
+                        .collect::<Vec<Json>>(),
+                ),
+            )])),
+            "variables" => self.variables(args),
+            "source" => Ok(Json::object(vec![
+                ("content", Json::from(listing(&self.current_image()))),
+                ("mimeType", Json::from("text/x-intcode")),
+            ])),
+            "disassemble" => self.disassemble(args),
+            "evaluate" => self.evaluate(args),
+            "disconnect" | "terminate" => {
+                self.finished = true;
+                Ok(Json::object(vec![]))
+            }
+            _ => Err(format!("unsupported command {}", command)),
+        }
+    }
+
+    fn program(&mut self) -> Result<&mut Program, String> {
+        self.program
+            .as_mut()
+            .ok_or_else(|| "no program launched".to_string())
+    }
+
+    // The image with the memory as modified by the program
+    fn current_image(&self) -> Image {
+        let mut image = self.image.clone();
+        if let Some(program) = &self.program {
+            image.memory = program.dump(0..image.memory.len());
+        }
+        image
+    }
+
+    // launch arguments : program (image file) or image (its content), inputs, stopOnEntry,
+    // stepBudget (steps run by a continue)
+    fn launch(&mut self, args: &Json) -> Result<Json, String> {
+        self.image = match (args.get("program").as_str(), args.get("image").as_str()) {
+            (Some(file), _) => std::fs::read_to_string(file)
+                .map_err(|e| format!("{} : {}", file, e))?
+                .parse()?,
+            (None, Some(content)) => content.parse()?,
+            (None, None) => return Err("missing program".to_string()),
+        };
+        let mut program = self.image.to_program();
+        program.input.extend(
+            args.get("inputs")
+                .as_array()
+                .iter()
+                .filter_map(Json::as_isize),
+        );
+        self.program = Some(program);
+        self.lines = disassemble(&self.image);
+        self.stop_on_entry = args.get("stopOnEntry").as_bool().unwrap_or(false);
+        self.continue_budget = args
+            .get("stepBudget")
+            .as_isize()
+            .filter(|&budget| budget > 0)
+            .map_or(CONTINUE_BUDGET, |budget| budget as usize);
+        Ok(Json::object(vec![]))
+    }
+
+    fn set_breakpoints(&mut self, args: &Json) -> Result<Json, String> {
+        self.source_breakpoints.clear();
+        let mut breakpoints = Vec::new();
+        for breakpoint in args.get("breakpoints").as_array() {
+            let line = breakpoint.get("line").as_isize().unwrap_or(0);
+            let address = self
+                .lines
+                .get((line - 1).max(0) as usize)
+                .filter(|_| line >= 1)
+                .map(|l| l.address);
+            if let Some(address) = address {
+                self.source_breakpoints.insert(address);
+            }
+            breakpoints.push(Json::object(vec![
+                ("verified", Json::from(address.is_some())),
+                ("line", Json::from(line)),
+            ]));
+        }
+        self.update_breakpoints()?;
+        Ok(Json::object(vec![("breakpoints", Json::from(breakpoints))]))
+    }
+
+    fn set_instruction_breakpoints(&mut self, args: &Json) -> Result<Json, String> {
+        self.instruction_breakpoints.clear();
+        let mut breakpoints = Vec::new();
+        for breakpoint in args.get("breakpoints").as_array() {
+            let address = breakpoint
+                .get("instructionReference")
+                .as_str()
+                .and_then(|reference| reference.parse::<isize>().ok())
+                .map(|address| address + breakpoint.get("offset").as_isize().unwrap_or(0))
+                .filter(|&address| address >= 0);
+            if let Some(address) = address {
+                self.instruction_breakpoints.insert(address as usize);
+            }
+            breakpoints.push(Json::object(vec![(
+                "verified",
+                Json::from(address.is_some()),
+            )]));
+        }
+        self.update_breakpoints()?;
+        Ok(Json::object(vec![("breakpoints", Json::from(breakpoints))]))
+    }
+
+    fn update_breakpoints(&mut self) -> Result<(), String> {
+        let breakpoints = self
+            .source_breakpoints
+            .union(&self.instruction_breakpoints)
+            .cloned()
+            .collect();
+        self.program()?.breakpoints = breakpoints;
+        Ok(())
+    }
+
+    // Runs for the given number of steps, or until the continue budget, then reports why the
+    // program stopped
+    fn resume(
+        &mut self,
+        steps: Option<usize>,
+        events: &mut Vec<(&'static str, Json)>,
+    ) -> Result<(), String> {
+        let budget = steps.unwrap_or(self.continue_budget);
+        let program = self.program()?;
+        program.execute_with_budget(budget);
+        let outputs: Vec<isize> = program.output.drain(..).collect();
+        let state = program.state.clone();
+        for output in outputs {
+            events.push((
+                "output",
+                Json::object(vec![
+                    ("category", Json::from("stdout")),
+                    ("output", Json::from(format!("{}\n", output))),
+                ]),
+            ));
+        }
+        self.lines = disassemble(&self.current_image());
+        match state {
+            ProgramState::Halted { .. } => {
+                events.push(("exited", Json::object(vec![("exitCode", Json::Number(0))])));
+                events.push(("terminated", Json::object(vec![])));
+            }
+            ProgramState::Breakpoint { .. } => events.push(self.stopped("breakpoint", None)),
+            ProgramState::BudgetExhausted { .. } if steps.is_some() => {
+                events.push(self.stopped("step", None))
+            }
+            ProgramState::BudgetExhausted { .. } => {
+                events.push(self.stopped("pause", Some(state.to_string())))
+            }
+            ProgramState::Waiting { .. } => {
+                events.push(self.stopped("pause", Some(state.to_string())))
+            }
+            _ => events.push(self.stopped("exception", Some(state.to_string()))),
+        }
+        Ok(())
+    }
+
+    fn stopped(&self, reason: &str, text: Option<String>) -> (&'static str, Json) {
+        let mut body = vec![
+            ("reason", Json::from(reason)),
+            ("threadId", Json::from(THREAD)),
+            ("allThreadsStopped", Json::from(true)),
+        ];
+        if let Some(text) = text {
+            body.push(("description", Json::from(text.clone())));
+            body.push(("text", Json::from(text)));
+        }
+        ("stopped", Json::object(body))
+    }
+
+    // Line of the listing holding the address
+    fn line_of(&self, address: usize) -> Option<usize> {
+        self.lines
+            .iter()
+            .position(|line| (line.address..line.address + line.size).contains(&address))
+    }
+
+    fn stack_trace(&mut self) -> Result<Json, String> {
+        let idx = self.program()?.idx();
+        // the frame is named after the closest symbol before the instruction
+        let name = self
+            .image
+            .symbols
+            .range(..=idx)
+            .next_back()
+            .map(|(_, symbol)| symbol.name.clone())
+            .unwrap_or_else(|| "program".to_string());
+        let frame = Json::object(vec![
+            ("id", Json::Number(1)),
+            ("name", Json::from(name)),
+            (
+                "line",
+                Json::from(self.line_of(idx).map_or(0, |line| line + 1)),
+            ),
+            ("column", Json::Number(1)),
+            (
+                "source",
+                Json::object(vec![
+                    ("name", Json::from(self.source_name())),
+                    ("sourceReference", Json::from(SOURCE)),
+                ]),
+            ),
+            ("instructionPointerReference", Json::from(idx.to_string())),
+        ]);
+        Ok(Json::object(vec![
+            ("stackFrames", Json::from(vec![frame])),
+            ("totalFrames", Json::Number(1)),
+        ]))
+    }
+
+    fn source_name(&self) -> String {
+        format!(
+            "{}.intcode",
+            self.image.name.as_deref().unwrap_or("program")
+        )
+    }
+
+    fn variables(&mut self, args: &Json) -> Result<Json, String> {
+        let image = self.current_image();
+        let program = self.program()?;
+        let variables: Vec<(String, String)> = match args.get("variablesReference").as_isize() {
+            Some(REGISTERS) => vec![
+                ("idx".to_string(), program.idx().to_string()),
+                (
+                    "relative_base".to_string(),
+                    program.relative_base().to_string(),
+                ),
+                ("steps".to_string(), program.steps().to_string()),
+                ("state".to_string(), program.state.to_string()),
+            ],
+            Some(MEMORY) => (program.idx()..program.idx() + MEMORY_WINDOW)
+                .map(|address| {
+                    let name = match image.symbol(address) {
+                        Some(symbol) => format!("[{}] {}", address, symbol.name),
+                        None => format!("[{}]", address),
+                    };
+                    (name, program.peek(address).to_string())
+                })
+                .collect(),
+            Some(IO) => vec![
+                ("input".to_string(), format!("{:?}", program.input)),
+                ("output".to_string(), format!("{:?}", program.output)),
+            ],
+            _ => return Err("unknown variables reference".to_string()),
+        };
+        Ok(Json::object(vec![(
+            "variables",
+            Json::from(
+                variables
+                    .into_iter()
+                    .map(|(name, value)| {
+                        Json::object(vec![
+                            ("name", Json::from(name)),
+                            ("value", Json::from(value)),
+                            ("variablesReference", Json::Number(0)),
+                        ])
+                    })
+                    .collect::<Vec<Json>>(),
+            ),
+        )]))
+    }
+
+    fn disassemble(&mut self, args: &Json) -> Result<Json, String> {
+        let address = args
+            .get("memoryReference")
+            .as_str()
+            .and_then(|reference| reference.parse::<usize>().ok())
+            .ok_or("bad memory reference")?;
+        let start = self.line_of(address).ok_or("address out of the image")? as isize
+            + args.get("instructionOffset").as_isize().unwrap_or(0);
+        let count = args.get("instructionCount").as_isize().unwrap_or(0);
+        let end = self.lines.last().map_or(0, |line| line.address + line.size) as isize;
+        // lines out of the listing are padded with invalid instructions, one cell each
+        let instructions: Vec<Json> = (start..start + count)
+            .map(
+                |line| match self.lines.get(line as usize).filter(|_| line >= 0) {
+                    Some(line) => Json::object(vec![
+                        ("address", Json::from(line.address.to_string())),
+                        ("instruction", Json::from(line.text.clone())),
+                        (
+                            "symbol",
+                            Json::from(
+                                self.image
+                                    .symbol(line.address)
+                                    .map_or("", |symbol| &symbol.name),
+                            ),
+                        ),
+                    ]),
+                    None => {
+                        let address = if line < 0 {
+                            line
+                        } else {
+                            end + line - self.lines.len() as isize
+                        };
+                        Json::object(vec![
+                            ("address", Json::from(address.to_string())),
+                            ("instruction", Json::from("??")),
+                            ("presentationHint", Json::from("invalid")),
+                        ])
+                    }
+                },
+            )
+            .collect();
+        Ok(Json::object(vec![(
+            "instructions",
+            Json::from(instructions),
+        )]))
+    }
+
+    // [address], symbol, idx, rb, or "input 1,2" to queue inputs
+    fn evaluate(&mut self, args: &Json) -> Result<Json, String> {
+        let expression = args.get("expression").as_str().unwrap_or("").trim();
+        let address = self.image.address_of(expression);
+        let program = self.program()?;
+        let result = if let Some(inputs) = expression.strip_prefix("input ") {
+            for input in inputs.split(',') {
+                let input = input.trim();
+                program
+                    .input
+                    .push_back(input.parse().map_err(|_| format!("bad input {}", input))?);
+            }
+            format!("{:?}", program.input)
+        } else if expression == "idx" {
+            program.idx().to_string()
+        } else if expression == "rb" || expression == "relative_base" {
+            program.relative_base().to_string()
+        } else if let Some(address) = address {
+            program.peek(address).to_string()
+        } else {
+            let address = expression
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse()
+                .map_err(|_| format!("can't evaluate {}", expression))?;
+            program.peek(address).to_string()
+        };
+        Ok(Json::object(vec![
+            ("result", Json::from(result)),
+            ("variablesReference", Json::Number(0)),
+        ]))
+    }
+}
+
+// Content-Length framed message, None at the end of the input. A malformed or too large
+// message is read entirely and given as an error, so that the next one can be read
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Result<Json, String>>> {
+    let mut length = Err(String::from("missing Content-Length header"));
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("bad header {}", header));
+        }
+    }
+    let length = match length {
+        Ok(length) => length,
+        Err(message) => return Ok(Some(Err(message))),
+    };
+    if length > MAX_CONTENT_LENGTH {
+        let skipped = io::copy(&mut (&mut *input).take(length as u64), &mut io::sink())?;
+        if skipped < length as u64 {
+            return Ok(None);
+        }
+        return Ok(Some(Err(format!(
+            "message of {} bytes, the limit is {}",
+            length, MAX_CONTENT_LENGTH
+        ))));
+    }
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+    Ok(Some(
+        String::from_utf8(content)
+            .map_err(|e| e.to_string())
+            .and_then(|content| content.parse()),
+    ))
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Json) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+pub fn serve<R: BufRead, W: Write>(mut input: R, mut output: W) -> Result<(), String> {
+    let mut session = Session::new();
+    while !session.finished {
+        let messages = match read_message(&mut input).map_err(|e| e.to_string())? {
+            Some(Ok(request)) => session.handle(&request),
+            Some(Err(message)) => vec![session.reject(message)],
+            None => return Ok(()),
+        };
+        for message in messages {
+            write_message(&mut output, &message).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE: &str = "name = double\n@9 value\n3,9,102,2,9,9,4,9,99,0";
+
+    fn request(session: &mut Session, command: &str, arguments: Json) -> Vec<Json> {
+        session.handle(&Json::object(vec![
+            ("seq", Json::Number(1)),
+            ("type", Json::from("request")),
+            ("command", Json::from(command)),
+            ("arguments", arguments),
+        ]))
+    }
+
+    fn events(messages: &[Json]) -> Vec<String> {
+        messages
+            .iter()
+            .filter_map(|m| m.get("event").as_str())
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    fn launched(inputs: Vec<isize>) -> Session {
+        let mut session = Session::new();
+        request(&mut session, "initialize", Json::object(vec![]));
+        let launch = request(
+            &mut session,
+            "launch",
+            Json::object(vec![
+                ("image", Json::from(IMAGE)),
+                (
+                    "inputs",
+                    Json::from(inputs.into_iter().map(Json::from).collect::<Vec<Json>>()),
+                ),
+            ]),
+        );
+        assert_eq!(launch[0].get("success"), &Json::Bool(true));
+        session
+    }
+
+    #[test]
+    fn run_to_a_breakpoint_then_step() {
+        let mut session = launched(vec![21]);
+        // line 3 of the listing is the output instruction
+        let breakpoints = request(
+            &mut session,
+            "setBreakpoints",
+            Json::object(vec![(
+                "breakpoints",
+                Json::from(vec![Json::object(vec![("line", Json::Number(3))])]),
+            )]),
+        );
+        assert_eq!(
+            breakpoints[0].get("body").get("breakpoints").as_array()[0].get("verified"),
+            &Json::Bool(true)
+        );
+
+        let stopped = request(&mut session, "configurationDone", Json::object(vec![]));
+        assert_eq!(events(&stopped), vec!["stopped"]);
+        assert_eq!(
+            stopped[1].get("body").get("reason").as_str(),
+            Some("breakpoint")
+        );
+
+        let trace = request(&mut session, "stackTrace", Json::object(vec![]));
+        let frame = &trace[0].get("body").get("stackFrames").as_array()[0];
+        assert_eq!(frame.get("line"), &Json::Number(3));
+        assert_eq!(frame.get("instructionPointerReference").as_str(), Some("6"));
+        assert_eq!(
+            frame.get("source").get("name").as_str(),
+            Some("double.intcode")
+        );
+
+        let evaluate = request(
+            &mut session,
+            "evaluate",
+            Json::object(vec![("expression", Json::from("value"))]),
+        );
+        assert_eq!(evaluate[0].get("body").get("result").as_str(), Some("42"));
+
+        let step = request(&mut session, "next", Json::object(vec![]));
+        assert_eq!(events(&step), vec!["output", "stopped"]);
+        assert_eq!(step[1].get("body").get("output").as_str(), Some("42\n"));
+
+        let end = request(&mut session, "continue", Json::object(vec![]));
+        assert_eq!(events(&end), vec!["exited", "terminated"]);
+    }
+
+    #[test]
+    fn continue_past_a_breakpoint_reached_by_a_step() {
+        let mut session = launched(vec![21]);
+        let lines = vec![2, 3]
+            .into_iter()
+            .map(|line| Json::object(vec![("line", Json::Number(line))]))
+            .collect::<Vec<Json>>();
+        request(
+            &mut session,
+            "setBreakpoints",
+            Json::object(vec![("breakpoints", Json::from(lines))]),
+        );
+        let stopped = request(&mut session, "configurationDone", Json::object(vec![]));
+        assert_eq!(
+            stopped[1].get("body").get("reason").as_str(),
+            Some("breakpoint")
+        );
+        // the step lands on the breakpoint of line 3
+        let step = request(&mut session, "next", Json::object(vec![]));
+        assert_eq!(step[1].get("body").get("reason").as_str(), Some("step"));
+        let end = request(&mut session, "continue", Json::object(vec![]));
+        assert_eq!(events(&end), vec!["output", "exited", "terminated"]);
+        assert_eq!(end[1].get("body").get("output").as_str(), Some("42\n"));
+    }
+
+    #[test]
+    fn inspect_registers_and_wait_for_inputs() {
+        let mut session = launched(vec![]);
+        let waiting = request(&mut session, "configurationDone", Json::object(vec![]));
+        assert_eq!(
+            waiting[1].get("body").get("text").as_str(),
+            Some("waiting for input at 0")
+        );
+        let registers = request(
+            &mut session,
+            "variables",
+            Json::object(vec![("variablesReference", Json::from(REGISTERS))]),
+        );
+        let registers = registers[0].get("body").get("variables").as_array();
+        assert_eq!(registers[0].get("name").as_str(), Some("idx"));
+        assert_eq!(registers[1].get("value").as_str(), Some("0"));
+
+        request(
+            &mut session,
+            "evaluate",
+            Json::object(vec![("expression", Json::from("input 5"))]),
+        );
+        let end = request(&mut session, "continue", Json::object(vec![]));
+        assert_eq!(events(&end), vec!["output", "exited", "terminated"]);
+    }
+
+    #[test]
+    fn endless_program_pauses_after_the_step_budget() {
+        let mut session = Session::new();
+        request(
+            &mut session,
+            "launch",
+            Json::object(vec![
+                ("image", Json::from("1105,1,0")),
+                ("stepBudget", Json::Number(100)),
+            ]),
+        );
+        let paused = request(&mut session, "configurationDone", Json::object(vec![]));
+        assert_eq!(events(&paused), vec!["stopped"]);
+        assert_eq!(paused[1].get("body").get("reason").as_str(), Some("pause"));
+        assert_eq!(
+            paused[1].get("body").get("text").as_str(),
+            Some("step budget exhausted at 0")
+        );
+        let paused = request(&mut session, "continue", Json::object(vec![]));
+        assert_eq!(paused[1].get("body").get("reason").as_str(), Some("pause"));
+        let registers = request(
+            &mut session,
+            "variables",
+            Json::object(vec![("variablesReference", Json::from(REGISTERS))]),
+        );
+        let registers = registers[0].get("body").get("variables").as_array();
+        assert_eq!(registers[2].get("value").as_str(), Some("200"));
+    }
+
+    #[test]
+    fn disassemble_around_an_address() {
+        let mut session = launched(vec![]);
+        let disassembly = request(
+            &mut session,
+            "disassemble",
+            Json::object(vec![
+                ("memoryReference", Json::from("2")),
+                ("instructionOffset", Json::Number(-1)),
+                ("instructionCount", Json::Number(3)),
+            ]),
+        );
+        let instructions: Vec<&str> = disassembly[0]
+            .get("body")
+            .get("instructions")
+            .as_array()
+            .iter()
+            .filter_map(|i| i.get("instruction").as_str())
+            .collect();
+        assert_eq!(
+            instructions,
+            vec!["IN [value]", "MUL 2, [value], [value]", "OUT [value]"]
+        );
+        let padded = request(
+            &mut session,
+            "disassemble",
+            Json::object(vec![
+                ("memoryReference", Json::from("0")),
+                ("instructionOffset", Json::Number(-1)),
+                ("instructionCount", Json::Number(8)),
+            ]),
+        );
+        let addresses: Vec<&str> = padded[0]
+            .get("body")
+            .get("instructions")
+            .as_array()
+            .iter()
+            .filter_map(|i| i.get("address").as_str())
+            .collect();
+        assert_eq!(addresses, vec!["-1", "0", "2", "6", "8", "9", "10", "11"]);
+        let unknown = request(&mut session, "stepIn", Json::object(vec![]));
+        assert_eq!(
+            unknown[0].get("message").as_str(),
+            Some("unsupported command stepIn")
+        );
+    }
+
+    #[test]
+    fn serve_framed_messages() {
+        let request = |command: &str| {
+            Json::object(vec![
+                ("seq", Json::Number(1)),
+                ("command", Json::from(command)),
+            ])
+        };
+        let mut input = Vec::new();
+        write_message(&mut input, &request("threads")).unwrap();
+        // malformed messages are rejected without ending the session
+        input.extend(b"Content-Length: many\r\n\r\n");
+        input.extend(b"Content-Length: 3\r\n\r\n{]}");
+        input.extend(format!("Content-Length: {}\r\n\r\n", MAX_CONTENT_LENGTH + 1).as_bytes());
+        input.extend(vec![b' '; MAX_CONTENT_LENGTH + 1]);
+        write_message(&mut input, &request("disconnect")).unwrap();
+
+        let mut output = Vec::new();
+        serve(input.as_slice(), &mut output).unwrap();
+        let mut output = output.as_slice();
+        let mut next = || read_message(&mut output).unwrap().unwrap().unwrap();
+        let threads = next();
+        assert_eq!(
+            threads.get("body").get("threads").as_array()[0]
+                .get("name")
+                .as_str(),
+            Some("intcode")
+        );
+        for _ in 0..3 {
+            let rejected = next();
+            assert_eq!(rejected.get("success"), &Json::Bool(false));
+            assert!(rejected.get("message").as_str().is_some());
+        }
+        assert_eq!(next().get("command").as_str(), Some("disconnect"));
+        assert!(read_message(&mut output).unwrap().is_none());
+    }
+}
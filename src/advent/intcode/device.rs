@@ -0,0 +1,194 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::advent::intcode::rng::Rng;
+
+// Peripheral mapped on a range of addresses. Offsets are relative to the start of the range,
+// steps is the number of instructions executed by the program so far
+pub trait Device: Debug {
+    fn read(&mut self, offset: usize, steps: usize) -> isize;
+    // Same value as read, without its side effects : used to inspect the memory
+    fn peek(&self, offset: usize, steps: usize) -> isize;
+    fn write(&mut self, offset: usize, value: isize, steps: usize);
+    // Number of addresses handled, None when there is no limit
+    fn size(&self) -> Option<usize> {
+        None
+    }
+}
+
+// Devices are shared : a cloned program still talks to the same devices
+#[derive(Debug, Clone)]
+pub struct Mapping {
+    pub range: Range<usize>,
+    pub device: Rc<RefCell<dyn Device>>,
+}
+
+impl PartialEq for Mapping {
+    fn eq(&self, other: &Self) -> bool {
+        self.range == other.range && Rc::ptr_eq(&self.device, &other.device)
+    }
+}
+
+// Reads the number of executed instructions, writes are ignored
+#[derive(Debug, Default)]
+pub struct Clock {}
+
+// Reads a new pseudo random number in 0..modulo, writing a value sets the modulo
+#[derive(Debug)]
+pub struct Random {
+    rng: Rng,
+    modulo: usize,
+}
+
+// width x height cells, one per pixel
+#[derive(Debug)]
+pub struct Framebuffer {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<isize>,
+}
+
+impl Device for Clock {
+    fn read(&mut self, offset: usize, steps: usize) -> isize {
+        self.peek(offset, steps)
+    }
+
+    fn peek(&self, _: usize, steps: usize) -> isize {
+        steps as isize
+    }
+
+    fn write(&mut self, _: usize, _: isize, _: usize) {}
+}
+
+impl Random {
+    pub fn new(seed: u64, modulo: usize) -> Result<Self, String> {
+        if modulo == 0 {
+            return Err(String::from("the modulo must be positive"));
+        }
+        Ok(Random {
+            rng: Rng::new(seed),
+            modulo,
+        })
+    }
+}
+
+impl Device for Random {
+    fn read(&mut self, _: usize, _: usize) -> isize {
+        self.rng.below(self.modulo) as isize
+    }
+
+    // the number the next read will return
+    fn peek(&self, _: usize, _: usize) -> isize {
+        self.rng.clone().below(self.modulo) as isize
+    }
+
+    fn write(&mut self, _: usize, value: isize, _: usize) {
+        self.modulo = value.max(1) as usize;
+    }
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Result<Self, String> {
+        if width == 0 {
+            return Err(String::from("the width must be positive"));
+        }
+        Ok(Framebuffer {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        })
+    }
+
+    // '#' for lit pixels
+    pub fn render(&self) -> String {
+        self.pixels
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|&pixel| if pixel == 0 { '.' } else { '#' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+impl Device for Framebuffer {
+    fn read(&mut self, offset: usize, steps: usize) -> isize {
+        self.peek(offset, steps)
+    }
+
+    fn peek(&self, offset: usize, _: usize) -> isize {
+        self.pixels[offset]
+    }
+
+    fn write(&mut self, offset: usize, value: isize, _: usize) {
+        self.pixels[offset] = value;
+    }
+
+    fn size(&self) -> Option<usize> {
+        Some(self.pixels.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::Program;
+
+    #[test]
+    fn read_the_clock() {
+        // outputs the clock twice
+        let mut program = Program::new(vec![4, 100, 1101, 0, 0, 50, 4, 100, 99]);
+        program
+            .attach(100..101, Rc::new(RefCell::new(Clock::default())))
+            .unwrap();
+        assert_eq!(program.exchange(vec![]), vec![0, 2]);
+    }
+
+    #[test]
+    fn draw_in_the_framebuffer() {
+        // lights the diagonal of a 3x3 screen
+        let screen = Rc::new(RefCell::new(Framebuffer::new(3, 3).unwrap()));
+        assert!(Framebuffer::new(0, 3).is_err());
+        let mut program = Program::new(vec![1101, 1, 0, 100, 1101, 1, 0, 104, 1101, 1, 0, 108, 99]);
+        assert!(program.attach(100..110, screen.clone()).is_err());
+        program.attach(100..109, screen.clone()).unwrap();
+        program.execute();
+        assert!(program.state.is_halted());
+        assert_eq!(screen.borrow().render(), "#..\n.#.\n..#");
+        assert_eq!(program.read(104), 1);
+        assert!(!program.snapshot().contains_key(&104));
+    }
+
+    #[test]
+    fn random_values_stay_in_range() {
+        // sets the modulo to 6 then outputs 20 rolls
+        let mut image = vec![1101, 6, 0, 200];
+        for _ in 0..20 {
+            image.extend(&[4, 200]);
+        }
+        image.push(99);
+        let mut program = Program::new(image);
+        let random = Rc::new(RefCell::new(Random::new(7, 100).unwrap()));
+        program.attach(200..201, random).unwrap();
+        let rolls = program.exchange(vec![]);
+        assert_eq!(rolls.len(), 20);
+        assert!(rolls.iter().all(|roll| (0..6).contains(roll)));
+        assert!(rolls.iter().any(|&roll| roll != rolls[0]));
+        assert!(Random::new(7, 0).is_err());
+    }
+
+    #[test]
+    fn peek_without_rolling() {
+        let mut program = Program::new(vec![4, 200, 99]);
+        let random = Rc::new(RefCell::new(Random::new(7, 1000).unwrap()));
+        program.attach(200..201, random).unwrap();
+        let next = program.peek(200);
+        assert_eq!(program.dump(200..201), vec![next]);
+        assert_eq!(program.exchange(vec![]), vec![next]);
+        assert_ne!(program.peek(200), next);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::advent::intcode::image::Image;
+use crate::advent::intcode::instruction::Instruction;
+use crate::advent::intcode::instruction::Mode;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub address: usize,
+    pub size: usize,
+    // None for cells which can't be decoded
+    pub instruction: Option<Instruction>,
+    pub text: String,
+}
+
+// Linear sweep : data placed between instructions may be decoded as instructions
+pub fn disassemble(image: &Image) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut address = 0;
+    while address < image.memory.len() {
+        let line = decode_line(image, address);
+        address += line.size;
+        lines.push(line);
+    }
+    lines
+}
+
+// The instruction at address, or a DATA line when the cell can't be decoded
+pub fn decode_line(image: &Image, address: usize) -> Line {
+    let memory = &image.memory;
+    match Instruction::decode(memory[address]) {
+        Some(instruction) if address + instruction.size() <= memory.len() => {
+            let operands: Vec<String> = (0..instruction.opcode.parameters())
+                .map(|param| operand(image, instruction.modes[param], memory[address + 1 + param]))
+                .collect();
+            Line {
+                address,
+                size: instruction.size(),
+                instruction: Some(instruction),
+                text: format!("{} {}", instruction.opcode.mnemonic(), operands.join(", "))
+                    .trim_end()
+                    .to_string(),
+            }
+        }
+        _ => Line {
+            address,
+            size: 1,
+            instruction: None,
+            text: format!("DATA {}", memory[address]),
+        },
+    }
+}
+
+pub fn listing(image: &Image) -> String {
+    disassemble(image)
+        .iter()
+        .map(|line| format_line(image, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn format_line(image: &Image, line: &Line) -> String {
+    let symbol = image.symbol(line.address);
+    let label = symbol.map(|s| format!("{}:", s.name)).unwrap_or_default();
+    let mut formatted = format!("{:>6}  {:<12} {}", line.address, label, line.text);
+    if let Some(comment) = symbol.and_then(|s| s.comment.as_ref()) {
+        formatted.push_str(&format!("  ; {}", comment));
+    }
+    formatted.trim_end().to_string()
+}
+
+fn operand(image: &Image, mode: Mode, value: isize) -> String {
+    match mode {
+        Mode::Immediate => value.to_string(),
+        Mode::Position => match image.symbol(value as usize).filter(|_| value >= 0) {
+            Some(symbol) => format!("[{}]", symbol.name),
+            None => format!("[{}]", value),
+        },
+        Mode::Relative if value < 0 => format!("[rb{}]", value),
+        Mode::Relative => format!("[rb+{}]", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::parse_input;
+
+    #[test]
+    fn disassemble_with_symbols() {
+        let image: Image = "@0 start ; entry point\n@9 value\n3,9,22101,2,-1,9,4,9,99,0,-5"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            listing(&image),
+            [
+                "     0  start:       IN [value]  ; entry point",
+                "     2               ADD 2, [rb-1], [rb+9]",
+                "     6               OUT [value]",
+                "     8               HALT",
+                "     9  value:       DATA 0",
+                "    10               DATA -5",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn disassemble_puzzle_program() {
+        let lines = disassemble(&Image::new(parse_input("09")));
+        assert_eq!(lines[0].text, "MUL 34463338, 34463338, [63]");
+        assert_eq!(
+            lines.iter().map(|line| line.size).sum::<usize>(),
+            parse_input("09").len()
+        );
+    }
+}
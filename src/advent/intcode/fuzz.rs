@@ -0,0 +1,167 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+use crate::advent::intcode::minimise::Minimiser;
+use crate::advent::intcode::rng::Rng;
+use crate::advent::intcode::Program;
+use crate::advent::intcode::ProgramState;
+
+const OPCODES: [isize; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Case {
+    pub image: Vec<isize>,
+    pub inputs: Vec<isize>,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub case: Case,
+    pub reason: String,
+}
+
+pub fn generate(rng: &mut Rng) -> Case {
+    let len = 1 + rng.below(32);
+    Case {
+        image: (0..len).map(|_| random_cell(rng, len)).collect(),
+        inputs: (0..rng.below(5)).map(|_| rng.between(-10, 10)).collect(),
+    }
+}
+
+pub fn mutate(case: &Case, rng: &mut Rng) -> Case {
+    let mut case = case.clone();
+    let len = case.image.len();
+    match rng.below(5) {
+        0 => case.image[rng.below(len)] = random_cell(rng, len),
+        1 => case.image.insert(rng.below(len + 1), random_cell(rng, len)),
+        2 if len > 1 => {
+            case.image.remove(rng.below(len));
+        }
+        3 => case.inputs.push(rng.between(-10, 10)),
+        _ => {
+            let idx = rng.below(len);
+            case.image[idx] = case.image[idx].wrapping_neg();
+        }
+    }
+    case
+}
+
+fn random_cell(rng: &mut Rng, len: usize) -> isize {
+    match rng.below(10) {
+        0..=3 => {
+            // mode 3 is invalid and must be handled too
+            let modes = (0..3).fold(0, |acc, _| acc * 10 + rng.between(0, 3));
+            modes * 100 + OPCODES[rng.below(OPCODES.len())]
+        }
+        4..=7 => rng.between(-2, len as isize + 2),
+        8 => rng.between(-1000, 1000),
+        _ => [isize::MIN, isize::MAX, isize::MAX / 2][rng.below(3)],
+    }
+}
+
+// Runs the case under a step budget and checks the VM invariants
+pub fn check(case: &Case, budget: usize) -> Result<(), String> {
+    let first = run(case, budget)?;
+    let second = run(case, budget)?;
+    if first != second {
+        return Err("execution is not deterministic".to_string());
+    }
+    Ok(())
+}
+
+fn run(case: &Case, budget: usize) -> Result<Program, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut program = Program::new(case.image.clone());
+        program.input.extend(&case.inputs);
+        while program.state == ProgramState::Running && program.steps() < budget {
+            let address = program.idx();
+            let steps = program.steps();
+            program.step();
+            if program.steps() > steps + 1 {
+                return Err(format!("step counter jumped at {}", address));
+            }
+            check_transition(&program, address)?;
+        }
+        Ok(program)
+    }))
+    .unwrap_or_else(|cause| {
+        Err(cause
+            .downcast_ref::<&str>()
+            .map(|s| format!("panic : {}", s))
+            .or_else(|| {
+                cause
+                    .downcast_ref::<String>()
+                    .map(|s| format!("panic : {}", s))
+            })
+            .unwrap_or_else(|| "panic".to_string()))
+    })
+}
+
+// A single step can only stop the program where it was executed, for a matching reason
+fn check_transition(program: &Program, address: usize) -> Result<(), String> {
+    let valid = match program.state {
+        ProgramState::Running => true,
+        ProgramState::Waiting { address: a } => a == address && program.input.is_empty(),
+        ProgramState::Halted { address: a } => a == address && program.peek(a) % 100 == 99,
+        ProgramState::OutOfMemory { address: a } => a == address && !program.is_loaded(a),
+        ProgramState::Faulted { address: a, .. } => a == address,
+        _ => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "unexpected state after step at {} : {}",
+            address, program.state
+        ))
+    }
+}
+
+pub fn fuzz(seed: u64, iterations: usize, budget: usize) -> Vec<Failure> {
+    let mut rng = Rng::new(seed);
+    let mut corpus: Vec<Case> = Vec::new();
+    let mut failures = Vec::new();
+    for _ in 0..iterations {
+        let case = if corpus.is_empty() || rng.below(2) == 0 {
+            generate(&mut rng)
+        } else {
+            mutate(&corpus[rng.below(corpus.len())], &mut rng)
+        };
+        if let Err(reason) = check(&case, budget) {
+            failures.push(Failure {
+                case: Minimiser::new(case.clone())
+                    .shrink_image()
+                    .minimise_with(&|c| check(c, budget).is_err())
+                    .unwrap_or(case),
+                reason,
+            });
+        } else {
+            corpus.push(case);
+        }
+    }
+    failures
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "{} with image {:?} and inputs {:?}",
+            self.reason, self.case.image, self.case.inputs
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vm_survives_fuzzing() {
+        let failures = fuzz(2019, 3000, 500);
+        assert!(failures.is_empty(), "{}", failures[0]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crate::advent::intcode::Program;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub comment: Option<String>,
+}
+
+// Intcode program with its metadata. Text format :
+//   # comment
+//   name = Breakout
+//   day = 13
+//   protocol = outputs (x, y, tile) triples, reads the joystick position
+//   @386 score ; current score
+//   1,380,379,385,...
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Image {
+    pub name: Option<String>,
+    pub day: Option<String>,
+    pub protocol: Option<String>,
+    pub symbols: BTreeMap<usize, Symbol>,
+    pub memory: Vec<isize>,
+}
+
+impl Image {
+    pub fn new(memory: Vec<isize>) -> Self {
+        Image {
+            memory,
+            ..Image::default()
+        }
+    }
+
+    pub fn load(file_name: &str) -> Result<Self, String> {
+        crate::read_file(file_name).parse()
+    }
+
+    pub fn save(&self, file_name: &str) -> std::io::Result<()> {
+        std::fs::write(file_name, self.to_string())
+    }
+
+    pub fn to_program(&self) -> Program {
+        Program::new(self.memory.clone())
+    }
+
+    pub fn symbol(&self, address: usize) -> Option<&Symbol> {
+        self.symbols.get(&address)
+    }
+
+    pub fn address_of(&self, name: &str) -> Option<usize> {
+        self.symbols
+            .iter()
+            .find(|(_, symbol)| symbol.name == name)
+            .map(|(&address, _)| address)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), String> {
+        if line.is_empty() || line.starts_with('#') {
+            Ok(())
+        } else if let Some(symbol) = line.strip_prefix('@') {
+            self.parse_symbol(symbol)
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = Some(value.trim().to_string());
+            match key.trim() {
+                "name" => self.name = value,
+                "day" => self.day = value,
+                "protocol" => self.protocol = value,
+                key => return Err(format!("unknown header {}", key)),
+            }
+            Ok(())
+        } else {
+            for number in line.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                self.memory.push(
+                    number
+                        .parse()
+                        .map_err(|_| format!("bad value {}", number))?,
+                );
+            }
+            Ok(())
+        }
+    }
+
+    fn parse_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        let (definition, comment) = match symbol.split_once(';') {
+            Some((definition, comment)) => (definition, Some(comment.trim().to_string())),
+            None => (symbol, None),
+        };
+        let mut parts = definition.split_whitespace();
+        let address = parts
+            .next()
+            .and_then(|a| a.parse().ok())
+            .ok_or_else(|| format!("bad symbol address in {}", symbol))?;
+        let name = parts
+            .next()
+            .ok_or_else(|| format!("missing symbol name in {}", symbol))?
+            .to_string();
+        self.symbols.insert(address, Symbol { name, comment });
+        Ok(())
+    }
+}
+
+impl FromStr for Image {
+    type Err = String;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let mut image = Image::default();
+        for (idx, line) in content.lines().enumerate() {
+            image
+                .parse_line(line.trim())
+                .map_err(|message| format!("line {} : {}", idx + 1, message))?;
+        }
+        Ok(image)
+    }
+}
+
+impl Display for Image {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let headers = [
+            ("name", &self.name),
+            ("day", &self.day),
+            ("protocol", &self.protocol),
+        ];
+        for (key, value) in headers.iter() {
+            if let Some(value) = value {
+                writeln!(f, "{} = {}", key, value)?;
+            }
+        }
+        for (address, symbol) in &self.symbols {
+            write!(f, "@{} {}", address, symbol.name)?;
+            if let Some(comment) = &symbol.comment {
+                write!(f, " ; {}", comment)?;
+            }
+            writeln!(f)?;
+        }
+        let memory: Vec<String> = self.memory.iter().map(|v| v.to_string()).collect();
+        writeln!(f, "{}", memory.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGE: &str = "# doubles its input
+name = Double
+day = 0
+protocol = reads a number, outputs its double
+@9 value ; the input
+3,9,
+102,2,9,9,4,9
+99,0
+";
+
+    #[test]
+    fn parse_image() {
+        let image: Image = IMAGE.parse().unwrap();
+        assert_eq!(image.name, Some("Double".to_string()));
+        assert_eq!(image.address_of("value"), Some(9));
+        assert_eq!(
+            image.symbol(9).unwrap().comment,
+            Some("the input".to_string())
+        );
+        assert_eq!(image.memory, vec![3, 9, 102, 2, 9, 9, 4, 9, 99, 0]);
+
+        let mut program = image.to_program();
+        assert_eq!(program.exchange(vec![21]), vec![42]);
+    }
+
+    #[test]
+    fn display_and_parse_back() {
+        let image: Image = IMAGE.parse().unwrap();
+        assert_eq!(image.to_string().parse::<Image>(), Ok(image));
+    }
+
+    #[test]
+    fn errors_report_the_line() {
+        assert_eq!(
+            "name = x\n@a value".parse::<Image>(),
+            Err("line 2 : bad symbol address in a value".to_string())
+        );
+        assert_eq!(
+            "1,2,x".parse::<Image>(),
+            Err("line 1 : bad value x".to_string())
+        );
+    }
+}
@@ -0,0 +1,171 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Add,
+    Mul,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustBase,
+    Halt,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub opcode: Opcode,
+    pub modes: [Mode; 3],
+}
+
+impl Opcode {
+    pub fn from_code(code: isize) -> Option<Self> {
+        Some(match code {
+            1 => Opcode::Add,
+            2 => Opcode::Mul,
+            3 => Opcode::Input,
+            4 => Opcode::Output,
+            5 => Opcode::JumpIfTrue,
+            6 => Opcode::JumpIfFalse,
+            7 => Opcode::LessThan,
+            8 => Opcode::Equals,
+            9 => Opcode::AdjustBase,
+            99 => Opcode::Halt,
+            _ => return None,
+        })
+    }
+
+    pub fn code(self) -> isize {
+        match self {
+            Opcode::Add => 1,
+            Opcode::Mul => 2,
+            Opcode::Input => 3,
+            Opcode::Output => 4,
+            Opcode::JumpIfTrue => 5,
+            Opcode::JumpIfFalse => 6,
+            Opcode::LessThan => 7,
+            Opcode::Equals => 8,
+            Opcode::AdjustBase => 9,
+            Opcode::Halt => 99,
+        }
+    }
+
+    pub fn parameters(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+
+    // Index of the parameter the instruction writes to
+    pub fn output(self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Mul | Opcode::LessThan | Opcode::Equals => Some(2),
+            Opcode::Input => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "ADD",
+            Opcode::Mul => "MUL",
+            Opcode::Input => "IN",
+            Opcode::Output => "OUT",
+            Opcode::JumpIfTrue => "JT",
+            Opcode::JumpIfFalse => "JF",
+            Opcode::LessThan => "LT",
+            Opcode::Equals => "EQ",
+            Opcode::AdjustBase => "ARB",
+            Opcode::Halt => "HALT",
+        }
+    }
+}
+
+impl Mode {
+    fn from_code(code: isize) -> Option<Self> {
+        match code {
+            0 => Some(Mode::Position),
+            1 => Some(Mode::Immediate),
+            2 => Some(Mode::Relative),
+            _ => None,
+        }
+    }
+
+    fn code(self) -> isize {
+        match self {
+            Mode::Position => 0,
+            Mode::Immediate => 1,
+            Mode::Relative => 2,
+        }
+    }
+}
+
+impl Instruction {
+    pub fn new(opcode: Opcode, modes: [Mode; 3]) -> Self {
+        Instruction { opcode, modes }
+    }
+
+    // Modes of unused parameters are ignored, as the VM does
+    pub fn decode(word: isize) -> Option<Self> {
+        if word < 0 {
+            return None;
+        }
+        let opcode = Opcode::from_code(word % 100)?;
+        let mut modes = [Mode::Position; 3];
+        for (idx, mode) in modes.iter_mut().enumerate().take(opcode.parameters()) {
+            *mode = Mode::from_code(word / 10isize.pow(idx as u32 + 2) % 10)?;
+        }
+        Some(Instruction { opcode, modes })
+    }
+
+    pub fn encode(&self) -> isize {
+        self.modes
+            .iter()
+            .take(self.opcode.parameters())
+            .enumerate()
+            .map(|(idx, mode)| mode.code() * 10isize.pow(idx as u32 + 2))
+            .sum::<isize>()
+            + self.opcode.code()
+    }
+
+    pub fn size(&self) -> usize {
+        1 + self.opcode.parameters()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_and_encode() {
+        let instruction = Instruction::decode(21101).unwrap();
+        assert_eq!(instruction.opcode, Opcode::Add);
+        assert_eq!(
+            instruction.modes,
+            [Mode::Immediate, Mode::Immediate, Mode::Relative]
+        );
+        assert_eq!(instruction.encode(), 21101);
+        assert_eq!(instruction.size(), 4);
+        assert_eq!(Instruction::decode(99).unwrap().size(), 1);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_words() {
+        assert_eq!(Instruction::decode(42), None);
+        assert_eq!(Instruction::decode(-1), None);
+        assert_eq!(Instruction::decode(301), None);
+        // mode of an unused parameter
+        assert!(Instruction::decode(30104).is_some());
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeSet;
+
+use crate::advent::intcode::fuzz::Case;
+use crate::advent::intcode::Program;
+
+const DEFAULT_BUDGET: usize = 1_000_000;
+
+// Shrinks the inputs, and optionally the image, of a case while the failure reproduces
+pub struct Minimiser {
+    case: Case,
+    budget: usize,
+    lines: bool,
+    image: bool,
+}
+
+impl Minimiser {
+    pub fn new(case: Case) -> Self {
+        Minimiser {
+            case,
+            budget: DEFAULT_BUDGET,
+            lines: false,
+            image: false,
+        }
+    }
+
+    pub fn budget(mut self, budget: usize) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    // ASCII scripts : whole lines are removed instead of single values
+    pub fn by_lines(mut self) -> Self {
+        self.lines = true;
+        self
+    }
+
+    // Also zeroes the image cells which aren't needed to reproduce the failure
+    pub fn shrink_image(mut self) -> Self {
+        self.image = true;
+        self
+    }
+
+    pub fn run(&self, case: &Case) -> Program {
+        let mut program = Program::new(case.image.clone());
+        program.input.extend(&case.inputs);
+        program.execute_with_budget(self.budget);
+        program
+    }
+
+    // None when the original case doesn't fail
+    pub fn minimise(&self, fails: &dyn Fn(&Program) -> bool) -> Option<Case> {
+        self.minimise_with(&|case| fails(&self.run(case)))
+    }
+
+    // Same as minimise, the failure being checked on the case instead of its execution
+    pub fn minimise_with(&self, fails: &dyn Fn(&Case) -> bool) -> Option<Case> {
+        let mut case = self.case.clone();
+        if !fails(&case) {
+            return None;
+        }
+
+        case.inputs = if self.lines {
+            ddmin(&split_lines(&case.inputs), &|lines: &[Vec<isize>]| {
+                fails(&Case {
+                    image: case.image.clone(),
+                    inputs: lines.concat(),
+                })
+            })
+            .concat()
+        } else {
+            ddmin(&case.inputs, &|inputs: &[isize]| {
+                fails(&Case {
+                    image: case.image.clone(),
+                    inputs: inputs.to_vec(),
+                })
+            })
+        };
+
+        if self.image {
+            let cells: Vec<usize> = (0..case.image.len())
+                .filter(|&idx| case.image[idx] != 0)
+                .collect();
+            let keep = |kept: &[usize]| {
+                let kept: BTreeSet<&usize> = kept.iter().collect();
+                Case {
+                    image: (0..case.image.len())
+                        .map(|idx| {
+                            if kept.contains(&idx) {
+                                case.image[idx]
+                            } else {
+                                0
+                            }
+                        })
+                        .collect(),
+                    inputs: case.inputs.clone(),
+                }
+            };
+            case = keep(&ddmin(&cells, &|kept: &[usize]| fails(&keep(kept))));
+        }
+        Some(case)
+    }
+}
+
+// Zeller's delta debugging : a 1-minimal subsequence of items which still fails,
+// assuming items fails
+pub fn ddmin<T: Clone>(items: &[T], fails: &dyn Fn(&[T]) -> bool) -> Vec<T> {
+    let mut items = items.to_vec();
+    let mut granularity = 2;
+    while items.len() >= 2 {
+        let size = items.len().div_ceil(granularity);
+        let chunks: Vec<(usize, usize)> = (0..items.len())
+            .step_by(size)
+            .map(|start| (start, (start + size).min(items.len())))
+            .collect();
+
+        if let Some(&(start, end)) = chunks
+            .iter()
+            .find(|(start, end)| fails(&items[*start..*end]))
+        {
+            items = items[start..end].to_vec();
+            granularity = 2;
+            continue;
+        }
+        let complement = |&(start, end): &(usize, usize)| [&items[..start], &items[end..]].concat();
+        if let Some(complement) = chunks.iter().map(complement).find(|c| fails(c)) {
+            items = complement;
+            granularity = (granularity - 1).max(2);
+            continue;
+        }
+        if granularity >= items.len() {
+            break;
+        }
+        granularity = (granularity * 2).min(items.len());
+    }
+    if items.len() == 1 && fails(&[]) {
+        items.clear();
+    }
+    items
+}
+
+// Every line keeps its trailing newline
+pub fn split_lines(inputs: &[isize]) -> Vec<Vec<isize>> {
+    inputs
+        .split_inclusive(|&value| value == '\n' as isize)
+        .map(|line| line.to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::parse_input;
+    use crate::advent::intcode::Fault;
+    use crate::advent::intcode::ProgramState;
+
+    fn ascii(script: &str) -> Vec<isize> {
+        script.chars().map(|c| c as isize).collect()
+    }
+
+    #[test]
+    fn ddmin_finds_the_failing_values() {
+        let items: Vec<usize> = (0..20).collect();
+        let fails = |items: &[usize]| items.contains(&3) && items.contains(&17);
+        assert_eq!(ddmin(&items, &fails), vec![3, 17]);
+        assert_eq!(ddmin(&items, &|_: &[usize]| true), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn shrink_a_springscript() {
+        // the droid falls : the outputs end with an ascii drawing instead of the hull damage
+        let case = Case {
+            image: parse_input("21"),
+            inputs: ascii("NOT A J\nNOT B T\nAND T J\nOR C T\nAND D T\nNOT T T\nWALK\n"),
+        };
+        let falls = |program: &Program| {
+            program.state.is_halted() && program.output.back().is_some_and(|&v| v < 128)
+        };
+        let minimised = Minimiser::new(case).by_lines().minimise(&falls).unwrap();
+        assert_eq!(minimised.inputs, ascii("WALK\n"));
+    }
+
+    #[test]
+    fn shrink_the_image() {
+        let case = Case {
+            image: vec![104, 1, 1101, 2, 3, 30, 42, 5, 6, 99],
+            inputs: vec![],
+        };
+        let unknown_opcode = |program: &Program| {
+            matches!(
+                program.state,
+                ProgramState::Faulted {
+                    fault: Fault::UnknownOpcode(42),
+                    ..
+                }
+            )
+        };
+        let minimiser = Minimiser::new(case).shrink_image();
+        assert_eq!(
+            minimiser.minimise(&unknown_opcode).unwrap().image,
+            vec![104, 0, 1101, 0, 0, 0, 42, 0, 0, 0]
+        );
+        assert_eq!(
+            minimiser.minimise(&|program| program.state.is_halted()),
+            None
+        );
+    }
+
+    #[test]
+    fn shrink_with_a_case_predicate() {
+        let case = Case {
+            image: vec![1101, 3, 42, 7, 99, 12],
+            inputs: vec![1, 2, 3],
+        };
+        assert_eq!(
+            Minimiser::new(case)
+                .shrink_image()
+                .minimise_with(&|c| c.image.contains(&42)),
+            Some(Case {
+                image: vec![0, 0, 42, 0, 0, 0],
+                inputs: vec![],
+            })
+        );
+    }
+}
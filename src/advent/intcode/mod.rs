@@ -0,0 +1,772 @@
+use std::cell::RefCell;
+use std::collections::vec_deque::VecDeque;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::ops::Range;
+use std::rc::Rc;
+
+use crate::advent::intcode::coverage::Coverage;
+use crate::advent::intcode::device::Device;
+use crate::advent::intcode::device::Mapping;
+use crate::advent::intcode::instruction::Instruction;
+use crate::advent::intcode::transcript::EventKind;
+use crate::advent::intcode::transcript::Transcript;
+
+pub mod compiler;
+pub mod conformance;
+pub mod coverage;
+pub mod dap;
+pub mod device;
+pub mod disasm;
+pub mod fuzz;
+pub mod image;
+pub mod instruction;
+pub mod json;
+pub mod minimise;
+pub mod optimise;
+pub mod rng;
+pub mod symbolic;
+pub mod transcript;
+pub mod watch;
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum ProgramState {
+    Running,
+    Waiting { address: usize },
+    Halted { address: usize },
+    OutOfMemory { address: usize },
+    Breakpoint { address: usize },
+    BudgetExhausted { address: usize },
+    Faulted { address: usize, fault: Fault },
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Fault {
+    UnknownOpcode(isize),
+    UnknownMode(u8),
+    NegativeAddress(isize),
+    Overflow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    operations: HashMap<usize, isize>,
+    idx: usize,
+    relative_base: isize,
+    steps: usize,
+    pub input: VecDeque<isize>,
+    pub output: VecDeque<isize>,
+    pub state: ProgramState,
+    pub breakpoints: HashSet<usize>,
+    pub transcript: Option<Transcript>,
+    pub coverage: Option<Coverage>,
+    devices: Vec<Mapping>,
+}
+
+struct OperationMode {
+    modes: (u8, u8, u8),
+}
+
+struct Add {
+    op_modes: OperationMode,
+}
+
+struct Mul {
+    op_modes: OperationMode,
+}
+
+struct Set {
+    op_modes: OperationMode,
+}
+
+struct Print {
+    op_modes: OperationMode,
+}
+
+struct JumpIf {
+    op_modes: OperationMode,
+    if_true: bool,
+}
+
+struct LessThan {
+    op_modes: OperationMode,
+}
+
+struct Equals {
+    op_modes: OperationMode,
+}
+
+struct RelativeBaseOffset {
+    op_modes: OperationMode,
+}
+
+struct Exit {}
+
+trait Operation {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault>;
+}
+
+fn parse_ope(input: isize) -> Result<Box<dyn Operation>, Fault> {
+    let op_modes = OperationMode {
+        modes: (
+            (input / 100 % 10) as u8,
+            (input / 1000 % 10) as u8,
+            (input / 10000 % 10) as u8,
+        ),
+    };
+    Ok(match input % 100 {
+        1 => Box::new(Add { op_modes }),
+        2 => Box::new(Mul { op_modes }),
+        3 => Box::new(Set { op_modes }),
+        4 => Box::new(Print { op_modes }),
+        5 => Box::new(JumpIf {
+            op_modes,
+            if_true: true,
+        }),
+        6 => Box::new(JumpIf {
+            op_modes,
+            if_true: false,
+        }),
+        7 => Box::new(LessThan { op_modes }),
+        8 => Box::new(Equals { op_modes }),
+        9 => Box::new(RelativeBaseOffset { op_modes }),
+        99 => Box::new(Exit {}),
+        _ => return Err(Fault::UnknownOpcode(input)),
+    })
+}
+
+impl Operation for Add {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let output_idx = prog.get_operation_idx(prog.idx + 3, self.op_modes.modes.2)?;
+        let value = prog
+            .get_value(prog.idx + 1, self.op_modes.modes.0)?
+            .checked_add(prog.get_value(prog.idx + 2, self.op_modes.modes.1)?)
+            .ok_or(Fault::Overflow)?;
+        prog.store(output_idx, value);
+        prog.idx += 4;
+        Ok(())
+    }
+}
+
+impl Operation for Mul {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let output_idx = prog.get_operation_idx(prog.idx + 3, self.op_modes.modes.2)?;
+        let value = prog
+            .get_value(prog.idx + 1, self.op_modes.modes.0)?
+            .checked_mul(prog.get_value(prog.idx + 2, self.op_modes.modes.1)?)
+            .ok_or(Fault::Overflow)?;
+        prog.store(output_idx, value);
+        prog.idx += 4;
+        Ok(())
+    }
+}
+
+impl Operation for Set {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let output_idx = prog.get_operation_idx(prog.idx + 1, self.op_modes.modes.0)?;
+        if let Some(input) = prog.input.pop_front() {
+            prog.store(output_idx, input);
+            prog.record(EventKind::Input, input);
+            prog.idx += 2;
+        } else {
+            prog.state = ProgramState::Waiting { address: prog.idx };
+        }
+        Ok(())
+    }
+}
+
+impl Operation for Print {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let value = prog.get_value(prog.idx + 1, self.op_modes.modes.0)?;
+        prog.output.push_back(value);
+        prog.record(EventKind::Output, value);
+        prog.idx += 2;
+        Ok(())
+    }
+}
+
+impl Operation for JumpIf {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let value = prog.get_value(prog.idx + 1, self.op_modes.modes.0)?;
+        // the target is only read when the jump is taken
+        if self.if_true == (value != 0) {
+            prog.idx = to_address(prog.get_value(prog.idx + 2, self.op_modes.modes.1)?)?;
+        } else {
+            prog.idx += 3;
+        }
+        Ok(())
+    }
+}
+
+impl Operation for LessThan {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let output_idx = prog.get_operation_idx(prog.idx + 3, self.op_modes.modes.2)?;
+        let value = if prog.get_value(prog.idx + 1, self.op_modes.modes.0)?
+            < prog.get_value(prog.idx + 2, self.op_modes.modes.1)?
+        {
+            1
+        } else {
+            0
+        };
+        prog.store(output_idx, value);
+        prog.idx += 4;
+        Ok(())
+    }
+}
+
+impl Operation for Equals {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let output_idx = prog.get_operation_idx(prog.idx + 3, self.op_modes.modes.2)?;
+        let value = if prog.get_value(prog.idx + 1, self.op_modes.modes.0)?
+            == prog.get_value(prog.idx + 2, self.op_modes.modes.1)?
+        {
+            1
+        } else {
+            0
+        };
+        prog.store(output_idx, value);
+        prog.idx += 4;
+        Ok(())
+    }
+}
+
+impl Operation for RelativeBaseOffset {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        let offset = prog.get_value(prog.idx + 1, self.op_modes.modes.0)?;
+        prog.relative_base = prog
+            .relative_base
+            .checked_add(offset)
+            .ok_or(Fault::Overflow)?;
+        prog.idx += 2;
+        Ok(())
+    }
+}
+
+impl Operation for Exit {
+    fn execute(&self, prog: &mut Program) -> Result<(), Fault> {
+        prog.state = ProgramState::Halted { address: prog.idx };
+        Ok(())
+    }
+}
+
+fn to_address(value: isize) -> Result<usize, Fault> {
+    if value < 0 {
+        Err(Fault::NegativeAddress(value))
+    } else {
+        Ok(value as usize)
+    }
+}
+
+impl Program {
+    pub fn new(opes: Vec<isize>) -> Self {
+        let operations = opes
+            .iter()
+            .cloned()
+            .enumerate()
+            .collect::<HashMap<usize, isize>>();
+        Program {
+            idx: 0,
+            relative_base: 0,
+            steps: 0,
+            operations,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            state: ProgramState::Running,
+            breakpoints: HashSet::new(),
+            transcript: None,
+            coverage: None,
+            devices: Vec::new(),
+        }
+    }
+
+    pub fn with_patches(opes: Vec<isize>, patches: &[(usize, isize)]) -> Self {
+        let mut program = Program::new(opes);
+        program.patch(patches);
+        program
+    }
+
+    pub fn patch(&mut self, patches: &[(usize, isize)]) {
+        for &(address, value) in patches {
+            self.write(address, value);
+        }
+    }
+
+    // Reads and writes on the range are handled by the device instead of the memory.
+    // The last attached device wins when ranges overlap
+    pub fn attach<D: Device + 'static>(
+        &mut self,
+        range: Range<usize>,
+        device: Rc<RefCell<D>>,
+    ) -> Result<(), String> {
+        if let Some(size) = device.borrow().size() {
+            if range.len() > size {
+                return Err(format!(
+                    "the device handles {} addresses, {:?} holds {}",
+                    size,
+                    range,
+                    range.len()
+                ));
+            }
+        }
+        self.devices.push(Mapping { range, device });
+        Ok(())
+    }
+
+    fn device(&self, address: usize) -> Option<&Mapping> {
+        self.devices
+            .iter()
+            .rev()
+            .find(|mapping| mapping.range.contains(&address))
+    }
+
+    pub fn read(&self, address: usize) -> isize {
+        match self.device(address) {
+            Some(mapping) => mapping
+                .device
+                .borrow_mut()
+                .read(address - mapping.range.start, self.steps),
+            None => *self.operations.get(&address).unwrap_or(&0),
+        }
+    }
+
+    // Same as read, without the side effects of devices
+    pub fn peek(&self, address: usize) -> isize {
+        match self.device(address) {
+            Some(mapping) => mapping
+                .device
+                .borrow()
+                .peek(address - mapping.range.start, self.steps),
+            None => *self.operations.get(&address).unwrap_or(&0),
+        }
+    }
+
+    pub fn write(&mut self, address: usize, value: isize) {
+        match self.device(address) {
+            Some(mapping) => {
+                mapping
+                    .device
+                    .borrow_mut()
+                    .write(address - mapping.range.start, value, self.steps)
+            }
+            None => {
+                self.operations.insert(address, value);
+            }
+        }
+    }
+
+    pub fn is_loaded(&self, address: usize) -> bool {
+        self.operations.contains_key(&address) || self.device(address).is_some()
+    }
+
+    pub fn dump(&self, addresses: Range<usize>) -> Vec<isize> {
+        addresses.map(|address| self.peek(address)).collect()
+    }
+
+    // Memory only : devices aren't read
+    pub fn snapshot(&self) -> BTreeMap<usize, isize> {
+        self.operations
+            .iter()
+            .map(|(&address, &value)| (address, value))
+            .collect()
+    }
+
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    pub fn set_idx(&mut self, idx: usize) {
+        self.idx = idx;
+    }
+
+    pub fn relative_base(&self) -> isize {
+        self.relative_base
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn set_relative_base(&mut self, relative_base: isize) {
+        self.relative_base = relative_base;
+    }
+
+    pub fn execute(&mut self) {
+        self.run(None);
+    }
+
+    // Stops with BudgetExhausted once max_steps instructions have been executed by this call
+    pub fn execute_with_budget(&mut self, max_steps: usize) {
+        self.run(Some(max_steps));
+    }
+
+    fn run(&mut self, budget: Option<usize>) {
+        // a program paused on a breakpoint address (by the breakpoint, the budget or an input)
+        // doesn't stop there again when it is resumed
+        let mut skip_breakpoint = self.state.is_paused() && self.state.address() == Some(self.idx);
+        let start = self.steps;
+        self.state = ProgramState::Running;
+        while self.state == ProgramState::Running {
+            if budget.is_some_and(|budget| self.steps - start >= budget) {
+                self.state = ProgramState::BudgetExhausted { address: self.idx };
+            } else if !skip_breakpoint && self.breakpoints.contains(&self.idx) {
+                self.state = ProgramState::Breakpoint { address: self.idx };
+            } else {
+                skip_breakpoint = false;
+                self.step();
+            }
+        }
+    }
+
+    // Feeds the inputs and runs until the program waits for the next ones
+    pub fn exchange<I>(&mut self, inputs: I) -> Vec<isize>
+    where
+        I: IntoIterator<Item = isize>,
+    {
+        self.input.extend(inputs);
+        self.execute();
+        self.output.drain(..).collect()
+    }
+
+    // Lazily runs the program to produce its outputs. Steps are run one by one : breakpoints
+    // and budgets are ignored, the iterators end when the program waits or is finished
+    pub fn outputs(&mut self) -> Outputs<'_> {
+        self.resume();
+        Outputs { program: self }
+    }
+
+    pub fn chunks(&mut self, size: usize) -> Chunks<'_> {
+        assert!(size > 0, "chunk size must be non-zero");
+        self.resume();
+        Chunks {
+            program: self,
+            size,
+        }
+    }
+
+    fn resume(&mut self) {
+        if self.state.is_paused() {
+            self.state = ProgramState::Running;
+        }
+    }
+
+    pub fn step(&mut self) {
+        let address = self.idx;
+        if !self.is_loaded(address) {
+            self.state = ProgramState::OutOfMemory { address };
+            return;
+        }
+        let word = self.read(address);
+        match parse_ope(word).and_then(|ope| ope.execute(self)) {
+            Ok(()) if !self.state.is_waiting() => {
+                self.steps += 1;
+                if let Some(coverage) = self.coverage.as_mut() {
+                    let size = Instruction::decode(word).map_or(1, |i| i.size());
+                    coverage.record_execution(address, size);
+                }
+            }
+            Ok(()) => (),
+            Err(fault) => self.state = ProgramState::Faulted { address, fault },
+        }
+    }
+
+    pub fn record_transcript(&mut self) {
+        self.transcript = Some(Transcript::default());
+    }
+
+    fn record(&mut self, kind: EventKind, value: isize) {
+        let step = self.steps;
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.record(step, kind, value);
+        }
+    }
+
+    pub fn record_coverage(&mut self) {
+        self.coverage = Some(Coverage::default());
+    }
+
+    fn get_value(&mut self, idx: usize, op_modes: u8) -> Result<isize, Fault> {
+        let address = self.get_operation_idx(idx, op_modes)?;
+        // immediate parameters are part of the instruction
+        if let Some(coverage) = self.coverage.as_mut().filter(|_| op_modes != 1) {
+            coverage.record_read(address);
+        }
+        Ok(self.read(address))
+    }
+
+    // Write done by an instruction
+    fn store(&mut self, address: usize, value: isize) {
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.record_write(address);
+        }
+        self.write(address, value);
+    }
+
+    fn get_operation_idx(&self, idx: usize, op_modes: u8) -> Result<usize, Fault> {
+        match op_modes {
+            0 => to_address(self.read(idx)),
+            1 => Ok(idx),
+            2 => to_address(
+                self.relative_base
+                    .checked_add(self.read(idx))
+                    .ok_or(Fault::Overflow)?,
+            ),
+            _ => Err(Fault::UnknownMode(op_modes)),
+        }
+    }
+}
+
+impl ProgramState {
+    pub fn is_waiting(&self) -> bool {
+        matches!(self, ProgramState::Waiting { .. })
+    }
+
+    pub fn is_halted(&self) -> bool {
+        matches!(self, ProgramState::Halted { .. })
+    }
+
+    // Waiting, breakpoint or budget : the program can be resumed
+    pub fn is_paused(&self) -> bool {
+        matches!(
+            self,
+            ProgramState::Waiting { .. }
+                | ProgramState::Breakpoint { .. }
+                | ProgramState::BudgetExhausted { .. }
+        )
+    }
+
+    // Halted, out of memory or faulted : resuming the program won't change anything
+    pub fn is_finished(&self) -> bool {
+        !self.is_paused() && *self != ProgramState::Running
+    }
+
+    pub fn address(&self) -> Option<usize> {
+        match *self {
+            ProgramState::Running => None,
+            ProgramState::Waiting { address }
+            | ProgramState::Halted { address }
+            | ProgramState::OutOfMemory { address }
+            | ProgramState::Breakpoint { address }
+            | ProgramState::BudgetExhausted { address }
+            | ProgramState::Faulted { address, .. } => Some(address),
+        }
+    }
+}
+
+impl Display for ProgramState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            ProgramState::Running => write!(f, "running"),
+            ProgramState::Waiting { address } => write!(f, "waiting for input at {}", address),
+            ProgramState::Halted { address } => write!(f, "halted at {}", address),
+            ProgramState::OutOfMemory { address } => {
+                write!(f, "ran off loaded memory at {}", address)
+            }
+            ProgramState::Breakpoint { address } => write!(f, "breakpoint at {}", address),
+            ProgramState::BudgetExhausted { address } => {
+                write!(f, "step budget exhausted at {}", address)
+            }
+            ProgramState::Faulted { address, fault } => {
+                write!(f, "faulted at {} : {}", address, fault)
+            }
+        }
+    }
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            Fault::UnknownOpcode(value) => write!(f, "unknown opcode {}", value),
+            Fault::UnknownMode(mode) => write!(f, "unknown parameter mode {}", mode),
+            Fault::NegativeAddress(address) => write!(f, "negative address {}", address),
+            Fault::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+pub struct Outputs<'a> {
+    program: &'a mut Program,
+}
+
+pub struct Chunks<'a> {
+    program: &'a mut Program,
+    size: usize,
+}
+
+impl Iterator for Outputs<'_> {
+    type Item = isize;
+
+    fn next(&mut self) -> Option<isize> {
+        while self.program.output.is_empty() && self.program.state == ProgramState::Running {
+            self.program.step();
+        }
+        self.program.output.pop_front()
+    }
+}
+
+// Incomplete chunks are left in the program output
+impl Iterator for Chunks<'_> {
+    type Item = Vec<isize>;
+
+    fn next(&mut self) -> Option<Vec<isize>> {
+        while self.program.output.len() < self.size && self.program.state == ProgramState::Running {
+            self.program.step();
+        }
+        if self.program.output.len() >= self.size {
+            Some(self.program.output.drain(..self.size).collect())
+        } else {
+            None
+        }
+    }
+}
+
+pub fn parse_input(day: &str) -> Vec<isize> {
+    crate::read_file(&format!("src/advent/day{}/input.txt", day))
+        .split(',')
+        .map(|number| {
+            number
+                .parse::<isize>()
+                .expect("input should contain only numbers")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patches_are_applied_before_execution() {
+        let mut program = Program::with_patches(vec![1, 0, 0, 0, 99], &[(1, 4), (2, 4)]);
+        program.execute();
+        assert_eq!(program.read(0), 198);
+    }
+
+    #[test]
+    fn memory_can_be_inspected_and_altered() {
+        let mut program = Program::new(vec![1101, 1, 2, 5, 99]);
+        program.execute();
+        assert_eq!(program.dump(3..7), vec![5, 99, 3, 0]);
+        assert_eq!(program.idx(), 4);
+        program.write(0, 99);
+        program.set_idx(0);
+        program.execute();
+        assert_eq!(program.state, ProgramState::Halted { address: 0 });
+        assert_eq!(program.read(1000), 0);
+    }
+
+    #[test]
+    fn outputs_are_produced_lazily() {
+        let mut program = Program::new(vec![104, 1, 104, 2, 104, 3, 99]);
+        assert_eq!(program.outputs().next(), Some(1));
+        assert_eq!(program.idx(), 2);
+        assert_eq!(program.outputs().collect::<Vec<isize>>(), vec![2, 3]);
+        assert_eq!(program.state, ProgramState::Halted { address: 6 });
+    }
+
+    #[test]
+    fn outputs_by_chunks() {
+        let mut program = Program::new(vec![104, 1, 104, 2, 104, 3, 99]);
+        assert_eq!(program.chunks(2).collect::<Vec<_>>(), vec![vec![1, 2]]);
+        assert_eq!(program.output, vec![3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn empty_chunks_are_rejected() {
+        Program::new(vec![104, 1, 99]).chunks(0);
+    }
+
+    #[test]
+    fn exchange_until_next_input() {
+        // outputs twice each input until 0
+        let mut program = Program::new(vec![
+            3, 100, 1006, 100, 14, 102, 2, 100, 100, 4, 100, 1105, 1, 0, 99,
+        ]);
+        assert_eq!(program.exchange(vec![21]), vec![42]);
+        assert_eq!(program.state, ProgramState::Waiting { address: 0 });
+        assert_eq!(program.exchange(vec![5]), vec![10]);
+        assert_eq!(program.exchange(vec![0]), vec![]);
+        assert_eq!(program.state, ProgramState::Halted { address: 14 });
+    }
+
+    #[test]
+    fn malformed_programs_fault() {
+        for (image, fault) in [
+            (vec![42], Fault::UnknownOpcode(42)),
+            (vec![1, -1, 0, 0, 99], Fault::NegativeAddress(-1)),
+            (vec![301, 0, 0, 0, 99], Fault::UnknownMode(3)),
+            (vec![1105, 1, -7], Fault::NegativeAddress(-7)),
+            (vec![1102, isize::MAX, 2, 0, 99], Fault::Overflow),
+        ] {
+            let mut program = Program::new(image);
+            program.execute();
+            assert_eq!(program.state, ProgramState::Faulted { address: 0, fault });
+            assert!(program.state.is_finished());
+            assert_eq!(program.steps(), 0);
+        }
+    }
+
+    #[test]
+    fn resume_past_a_breakpoint_reached_by_the_budget() {
+        let mut program = Program::new(vec![104, 1, 104, 2, 104, 3, 99]);
+        program.breakpoints.insert(2);
+        program.execute_with_budget(1);
+        assert_eq!(program.state, ProgramState::BudgetExhausted { address: 2 });
+        program.execute();
+        assert_eq!(program.state, ProgramState::Halted { address: 6 });
+        assert_eq!(program.output, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn resume_past_a_breakpoint_waiting_for_input() {
+        let mut program = Program::new(vec![3, 5, 4, 5, 99, 0]);
+        program.breakpoints.insert(0);
+        program.execute();
+        assert_eq!(program.state, ProgramState::Breakpoint { address: 0 });
+        program.execute();
+        assert_eq!(program.state, ProgramState::Waiting { address: 0 });
+        program.input.push_back(7);
+        program.execute();
+        assert_eq!(program.state, ProgramState::Halted { address: 4 });
+        assert_eq!(program.output, vec![7]);
+    }
+
+    #[test]
+    fn jump_not_taken_ignores_its_target() {
+        // position mode target at the negative address -1
+        let mut program = Program::new(vec![1005, 7, -1, 106, 1, -1, 99, 0]);
+        program.execute();
+        assert_eq!(program.state, ProgramState::Halted { address: 6 });
+    }
+
+    #[test]
+    fn running_off_memory_is_not_a_halt() {
+        let mut program = Program::new(vec![1101, 1, 2, 0]);
+        program.execute();
+        assert_eq!(program.state, ProgramState::OutOfMemory { address: 4 });
+        assert_eq!(
+            program.state.to_string(),
+            "ran off loaded memory at 4".to_string()
+        );
+    }
+
+    #[test]
+    fn stop_on_breakpoints_and_budget() {
+        let mut program = Program::new(vec![104, 1, 104, 2, 104, 3, 99]);
+        program.breakpoints.insert(2);
+        program.execute();
+        assert_eq!(program.state, ProgramState::Breakpoint { address: 2 });
+        assert_eq!(program.steps(), 1);
+        program.execute_with_budget(1);
+        assert_eq!(program.state, ProgramState::BudgetExhausted { address: 4 });
+        assert!(program.state.is_paused());
+        program.execute();
+        assert_eq!(program.state, ProgramState::Halted { address: 6 });
+        assert_eq!(program.steps(), 4);
+        assert_eq!(program.output, vec![1, 2, 3]);
+    }
+}
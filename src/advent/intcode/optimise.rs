@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::advent::intcode::coverage::Coverage;
+use crate::advent::intcode::instruction::Instruction;
+use crate::advent::intcode::instruction::Mode;
+use crate::advent::intcode::instruction::Opcode;
+use crate::advent::intcode::transcript::Divergence;
+use crate::advent::intcode::transcript::Transcript;
+use crate::advent::intcode::Program;
+
+#[derive(Debug)]
+pub struct Optimisation {
+    pub image: Vec<isize>,
+    pub folded: Vec<usize>,
+    pub threaded: Vec<usize>,
+    // Addresses never executed (data included), None when a computed jump hides the control flow
+    pub unreachable: Option<Vec<Range<usize>>>,
+}
+
+// Static view of the instructions reachable from address 0
+struct Flow {
+    instructions: BTreeMap<usize, Instruction>,
+    // cells read or written as data by position mode parameters
+    data: HashSet<usize>,
+    // a relative mode parameter may read or write any cell
+    relative: bool,
+    computed_jump: bool,
+    // relative accesses and computed jumps seen on the recorded runs, instead of any cell
+    resolved: bool,
+}
+
+// The optimised image must produce the outputs of every transcript recorded on the original one.
+// Without transcripts, a relative access or a computed jump may touch any cell : nothing is
+// rewritten in such programs (every puzzle from day 09). With transcripts, the cells they touch
+// are taken from the recorded runs, so the image is only optimised for inputs following them
+pub fn optimise(image: &[isize], transcripts: &[Transcript]) -> Result<Optimisation, Divergence> {
+    let mut flow = analyse(image);
+    if !transcripts.is_empty() {
+        flow.resolve(image, transcripts);
+    }
+    let mut optimised = image.to_vec();
+    let mut folded = Vec::new();
+    let mut threaded = Vec::new();
+
+    for (&address, &instruction) in &flow.instructions {
+        if flow.is_data(address, instruction) {
+            // self modifying code : keep it as is
+            continue;
+        }
+        if let Some(value) = fold(image, address, instruction) {
+            if image[address + 1..address + 3] != [value, 0] || instruction.opcode != Opcode::Add {
+                let add = Instruction::new(
+                    Opcode::Add,
+                    [Mode::Immediate, Mode::Immediate, instruction.modes[2]],
+                );
+                optimised[address] = add.encode();
+                optimised[address + 1] = value;
+                optimised[address + 2] = 0;
+                folded.push(address);
+            }
+        }
+        if let Some(target) = thread(image, &flow, address, instruction) {
+            optimised[address + 2] = target as isize;
+            threaded.push(address);
+        }
+    }
+
+    for transcript in transcripts {
+        verify(&optimised, transcript)?;
+    }
+    Ok(Optimisation {
+        image: optimised,
+        folded,
+        threaded,
+        unreachable: if flow.computed_jump {
+            None
+        } else {
+            Some(flow.unreachable(image.len()))
+        },
+    })
+}
+
+// Differential execution of the optimised image against the outputs recorded on the original one
+pub fn verify(image: &[isize], transcript: &Transcript) -> Result<(), Divergence> {
+    transcript.replay_outputs(Program::new(image.to_vec()))
+}
+
+fn analyse(image: &[isize]) -> Flow {
+    let mut flow = Flow {
+        instructions: BTreeMap::new(),
+        data: HashSet::new(),
+        relative: false,
+        computed_jump: false,
+        resolved: false,
+    };
+    let mut queue = vec![0];
+    while let Some(address) = queue.pop() {
+        if address >= image.len() || flow.instructions.contains_key(&address) {
+            continue;
+        }
+        let instruction = match Instruction::decode(image[address]) {
+            Some(instruction) if address + instruction.size() <= image.len() => instruction,
+            _ => continue,
+        };
+        flow.instructions.insert(address, instruction);
+        for param in 0..instruction.opcode.parameters() {
+            let value = image[address + 1 + param];
+            match instruction.modes[param] {
+                Mode::Position if value >= 0 => {
+                    flow.data.insert(value as usize);
+                }
+                Mode::Relative => flow.relative = true,
+                _ => (),
+            }
+        }
+
+        match instruction.opcode {
+            Opcode::Halt => (),
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                let jump_if = instruction.opcode == Opcode::JumpIfTrue;
+                let condition = immediate(image, address, instruction, 0);
+                // unknown conditions may go both ways
+                let taken = !matches!(condition, Some(c) if (c != 0) != jump_if);
+                let skipped = !matches!(condition, Some(c) if (c != 0) == jump_if);
+                if taken {
+                    match immediate(image, address, instruction, 1) {
+                        Some(target) if target >= 0 => queue.push(target as usize),
+                        Some(_) => (),
+                        None => flow.computed_jump = true,
+                    }
+                }
+                if skipped {
+                    queue.push(address + 3);
+                }
+            }
+            _ => queue.push(address + instruction.size()),
+        }
+    }
+    flow
+}
+
+impl Flow {
+    // Relative accesses and code behind computed jumps may touch any cell, unless resolved
+    fn is_data(&self, address: usize, instruction: Instruction) -> bool {
+        (!self.resolved && (self.relative || self.computed_jump))
+            || (address..address + instruction.size()).any(|cell| self.data.contains(&cell))
+    }
+
+    // Runs the original image on the recorded inputs : every cell read or written there is data,
+    // as is an instruction entered elsewhere than at its start
+    fn resolve(&mut self, image: &[isize], transcripts: &[Transcript]) {
+        let mut coverage = Coverage::default();
+        for transcript in transcripts {
+            let mut program = Program::new(image.to_vec());
+            program.record_coverage();
+            program.input.extend(transcript.inputs().map(|e| e.value));
+            program.execute_with_budget(transcript.budget());
+            coverage.merge(&program.coverage.unwrap_or_default());
+        }
+        self.data
+            .extend(coverage.read.keys().chain(coverage.written.keys()));
+        for &start in coverage.executed.keys() {
+            if let Some((&address, instruction)) = self.instructions.range(..start).next_back() {
+                if start < address + instruction.size() {
+                    self.data.insert(address);
+                }
+            }
+        }
+        self.resolved = true;
+    }
+
+    fn unreachable(&self, len: usize) -> Vec<Range<usize>> {
+        let mut executed = vec![false; len];
+        for (&address, instruction) in &self.instructions {
+            for cell in executed.iter_mut().skip(address).take(instruction.size()) {
+                *cell = true;
+            }
+        }
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (address, _) in executed.iter().enumerate().filter(|(_, &e)| !e) {
+            match ranges.last_mut() {
+                Some(range) if range.end == address => range.end += 1,
+                _ => ranges.push(address..address + 1),
+            }
+        }
+        ranges
+    }
+
+    // Target of a jump always taken, with an immediate target
+    fn unconditional_target(&self, image: &[isize], address: usize) -> Option<usize> {
+        let instruction = *self.instructions.get(&address)?;
+        let jump_if = match instruction.opcode {
+            Opcode::JumpIfTrue => true,
+            Opcode::JumpIfFalse => false,
+            _ => return None,
+        };
+        let condition = immediate(image, address, instruction, 0)?;
+        let target = immediate(image, address, instruction, 1)?;
+        if (condition != 0) == jump_if && target >= 0 && !self.is_data(address, instruction) {
+            Some(target as usize)
+        } else {
+            None
+        }
+    }
+}
+
+fn immediate(
+    image: &[isize],
+    address: usize,
+    instruction: Instruction,
+    param: usize,
+) -> Option<isize> {
+    if instruction.modes[param] == Mode::Immediate {
+        Some(image[address + 1 + param])
+    } else {
+        None
+    }
+}
+
+fn fold(image: &[isize], address: usize, instruction: Instruction) -> Option<isize> {
+    let a = immediate(image, address, instruction, 0)?;
+    let b = immediate(image, address, instruction, 1)?;
+    match instruction.opcode {
+        Opcode::Add => a.checked_add(b),
+        Opcode::Mul => a.checked_mul(b),
+        Opcode::LessThan => Some((a < b) as isize),
+        Opcode::Equals => Some((a == b) as isize),
+        _ => None,
+    }
+}
+
+// New target of a jump landing on a chain of unconditional jumps
+fn thread(image: &[isize], flow: &Flow, address: usize, instruction: Instruction) -> Option<usize> {
+    match instruction.opcode {
+        Opcode::JumpIfTrue | Opcode::JumpIfFalse => (),
+        _ => return None,
+    }
+    let original = immediate(image, address, instruction, 1).filter(|&t| t >= 0)?;
+    let mut target = original as usize;
+    let mut visited = HashSet::new();
+    visited.insert(address);
+    while let Some(next) = flow.unconditional_target(image, target) {
+        if !visited.insert(target) {
+            // infinite loop of jumps : keep it
+            return None;
+        }
+        target = next;
+    }
+    if target as isize != original {
+        Some(target)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::parse_input;
+
+    fn record(image: &[isize], inputs: &[isize]) -> Transcript {
+        let mut program = Program::new(image.to_vec());
+        program.record_transcript();
+        program.input.extend(inputs);
+        program.execute();
+        program.transcript.unwrap()
+    }
+
+    #[test]
+    fn fold_constant_arithmetic() {
+        let image = vec![1102, 6, 7, 13, 1107, 1, 2, 14, 4, 13, 4, 14, 99, 0, 0];
+        let optimisation = optimise(&image, &[record(&image, &[])]).unwrap();
+        assert_eq!(optimisation.folded, vec![0, 4]);
+        assert_eq!(optimisation.image[0..8], [1101, 42, 0, 13, 1101, 1, 0, 14]);
+    }
+
+    #[test]
+    fn self_modified_instructions_are_kept() {
+        // the first instruction is read as data by the output
+        let image = vec![1102, 6, 7, 9, 4, 0, 4, 9, 99, 0];
+        assert!(optimise(&image, &[]).unwrap().folded.is_empty());
+        // the output reads the folded cell through the relative base
+        let image = vec![109, 4, 204, 1, 1102, 6, 7, 11, 4, 11, 99, 0];
+        let optimisation = optimise(&image, &[record(&image, &[])]).unwrap();
+        assert!(optimisation.folded.is_empty());
+    }
+
+    #[test]
+    fn diverging_transcripts_are_rejected() {
+        let transcript = record(&[104, 1, 99], &[]);
+        assert_eq!(
+            optimise(&[104, 2, 99], &[transcript])
+                .err()
+                .map(|d| d.index),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn thread_jumps_and_mark_unreachable_code() {
+        let image = vec![1105, 1, 5, 99, 99, 1106, 0, 8, 104, 1, 99];
+        let optimisation = optimise(&image, &[record(&image, &[])]).unwrap();
+        assert_eq!(optimisation.threaded, vec![0]);
+        assert_eq!(optimisation.image[2], 8);
+        assert_eq!(
+            optimisation.unreachable,
+            Some(vec![Range { start: 3, end: 5 }])
+        );
+    }
+
+    #[test]
+    fn computed_jumps_hide_unreachable_code() {
+        let optimisation = optimise(&parse_input("09"), &[]).unwrap();
+        assert_eq!(optimisation.unreachable, None);
+    }
+
+    #[test]
+    fn relative_mode_programs_are_optimised_on_their_recorded_runs() {
+        let image = parse_input("09");
+        let transcripts = [record(&image, &[1]), record(&image, &[2])];
+        assert!(optimise(&image, &[]).unwrap().folded.is_empty());
+        let optimisation = optimise(&image, &transcripts).unwrap();
+        assert_eq!(optimisation.folded.len(), 27);
+        assert_eq!(optimisation.folded[0..3], [11, 65, 73]);
+    }
+
+    #[test]
+    fn optimised_puzzle_programs_behave_the_same() {
+        for (day, input) in &[("05", 1), ("05", 5), ("09", 1), ("09", 2)] {
+            let image = parse_input(day);
+            assert!(optimise(&image, &[record(&image, &[*input])]).is_ok());
+        }
+    }
+}
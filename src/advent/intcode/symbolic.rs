@@ -0,0 +1,739 @@
+use std::collections::vec_deque::VecDeque;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use crate::advent::intcode::Program;
+
+const DEFAULT_MAX_RUNS: usize = 100;
+const DEFAULT_BUDGET: usize = 1_000_000;
+// values tried by the constraint solver before giving up
+const MAX_SEARCH_NODES: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unknown {
+    // nth value consumed by the program
+    Input(usize),
+    Memory(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    pub unknown: Unknown,
+    pub domain: RangeInclusive<isize>,
+}
+
+// constant + sum(coefficient * variable), variables being indexes in the solver variables
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Linear {
+    pub constant: isize,
+    pub terms: BTreeMap<usize, isize>,
+}
+
+// Relation between an expression and 0
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Eq,
+    Ne,
+    Lt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constraint {
+    pub expr: Linear,
+    pub relation: Relation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    Output { index: usize, value: isize },
+    // value of a memory cell once the program is halted
+    Memory { address: usize, value: isize },
+    Reach(usize),
+}
+
+// Concrete value shadowed by its expression over the unknowns (None when it is not linear)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Value {
+    pub concrete: isize,
+    pub symbolic: Option<Linear>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    pub path: Vec<Constraint>,
+    pub outputs: Vec<Value>,
+    pub memory: HashMap<usize, Value>,
+    pub executed: HashSet<usize>,
+    pub halted: bool,
+}
+
+pub struct Solver {
+    program: Program,
+    variables: Vec<Variable>,
+    max_runs: usize,
+    budget: usize,
+}
+
+struct Machine {
+    memory: HashMap<usize, Value>,
+    idx: usize,
+    relative_base: isize,
+    inputs: VecDeque<Value>,
+    trace: Trace,
+    stop_at: Option<usize>,
+}
+
+impl Linear {
+    pub fn constant(value: isize) -> Self {
+        Linear {
+            constant: value,
+            terms: BTreeMap::new(),
+        }
+    }
+
+    pub fn variable(variable: usize) -> Self {
+        let mut terms = BTreeMap::new();
+        terms.insert(variable, 1);
+        Linear { constant: 0, terms }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn add(&self, other: &Linear) -> Option<Linear> {
+        let mut result = self.clone();
+        result.constant = result.constant.checked_add(other.constant)?;
+        for (&variable, &coefficient) in &other.terms {
+            let sum = result
+                .terms
+                .get(&variable)
+                .unwrap_or(&0)
+                .checked_add(coefficient)?;
+            if sum == 0 {
+                result.terms.remove(&variable);
+            } else {
+                result.terms.insert(variable, sum);
+            }
+        }
+        Some(result)
+    }
+
+    pub fn scale(&self, factor: isize) -> Option<Linear> {
+        if factor == 0 {
+            return Some(Linear::constant(0));
+        }
+        let mut terms = BTreeMap::new();
+        for (&variable, &coefficient) in &self.terms {
+            terms.insert(variable, coefficient.checked_mul(factor)?);
+        }
+        Some(Linear {
+            constant: self.constant.checked_mul(factor)?,
+            terms,
+        })
+    }
+
+    pub fn sub(&self, other: &Linear) -> Option<Linear> {
+        self.add(&other.scale(-1)?)
+    }
+}
+
+impl Relation {
+    pub fn negate(self) -> Self {
+        match self {
+            Relation::Eq => Relation::Ne,
+            Relation::Ne => Relation::Eq,
+            Relation::Lt => Relation::Ge,
+            Relation::Ge => Relation::Lt,
+        }
+    }
+
+    fn holds(self, value: i128) -> bool {
+        match self {
+            Relation::Eq => value == 0,
+            Relation::Ne => value != 0,
+            Relation::Lt => value < 0,
+            Relation::Ge => value >= 0,
+        }
+    }
+}
+
+impl Constraint {
+    pub fn negate(&self) -> Self {
+        Constraint {
+            expr: self.expr.clone(),
+            relation: self.relation.negate(),
+        }
+    }
+}
+
+impl Value {
+    fn constant(value: isize) -> Self {
+        Value {
+            concrete: value,
+            symbolic: Some(Linear::constant(value)),
+        }
+    }
+
+    fn opaque(value: isize) -> Self {
+        Value {
+            concrete: value,
+            symbolic: None,
+        }
+    }
+
+    // Linear expression when the value really depends on an unknown
+    fn expression(&self) -> Option<&Linear> {
+        self.symbolic.as_ref().filter(|s| !s.is_constant())
+    }
+}
+
+impl Solver {
+    pub fn new(program: &Program) -> Self {
+        Solver {
+            program: program.clone(),
+            variables: Vec::new(),
+            max_runs: DEFAULT_MAX_RUNS,
+            budget: DEFAULT_BUDGET,
+        }
+    }
+
+    pub fn unknown_input(mut self, index: usize, domain: RangeInclusive<isize>) -> Self {
+        self.variables.push(Variable {
+            unknown: Unknown::Input(index),
+            domain,
+        });
+        self
+    }
+
+    pub fn unknown_memory(mut self, address: usize, domain: RangeInclusive<isize>) -> Self {
+        self.variables.push(Variable {
+            unknown: Unknown::Memory(address),
+            domain,
+        });
+        self
+    }
+
+    pub fn max_runs(mut self, max_runs: usize) -> Self {
+        self.max_runs = max_runs;
+        self
+    }
+
+    pub fn budget(mut self, budget: usize) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    // Concolic search : runs the program with concrete values for the unknowns, then solves
+    // the collected path constraints to reach the goal or to explore another path
+    pub fn solve(&self, goal: Goal) -> Option<Vec<isize>> {
+        let mut queue = VecDeque::new();
+        queue.push_back(
+            self.variables
+                .iter()
+                .map(|v| *v.domain.start())
+                .collect::<Vec<isize>>(),
+        );
+        let mut seen = HashSet::new();
+        let mut runs = 0;
+
+        while let Some(assignment) = queue.pop_front() {
+            if runs >= self.max_runs {
+                break;
+            }
+            if !seen.insert(assignment.clone()) {
+                continue;
+            }
+            runs += 1;
+            let trace = self.trace(&assignment, goal);
+            if satisfies(&trace, goal) {
+                return Some(assignment);
+            }
+            if let Some(target) = goal_constraint(&trace, goal) {
+                let mut constraints = trace.path.clone();
+                constraints.push(target);
+                if let Some(solution) = solve_constraints(&constraints, &self.variables) {
+                    queue.push_front(solution);
+                }
+            }
+            for idx in (0..trace.path.len()).rev() {
+                let mut constraints = trace.path[..idx].to_vec();
+                constraints.push(trace.path[idx].negate());
+                if let Some(solution) = solve_constraints(&constraints, &self.variables) {
+                    queue.push_back(solution);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn trace(&self, assignment: &[isize], goal: Goal) -> Trace {
+        let mut memory: HashMap<usize, Value> = self
+            .program
+            .snapshot()
+            .into_iter()
+            .map(|(address, value)| (address, Value::constant(value)))
+            .collect();
+        let mut inputs: VecDeque<Value> = self
+            .program
+            .input
+            .iter()
+            .cloned()
+            .map(Value::constant)
+            .collect();
+        for (variable, (definition, &value)) in self.variables.iter().zip(assignment).enumerate() {
+            let cell = Value {
+                concrete: value,
+                symbolic: Some(Linear::variable(variable)),
+            };
+            match definition.unknown {
+                Unknown::Memory(address) => {
+                    memory.insert(address, cell);
+                }
+                Unknown::Input(index) => {
+                    while inputs.len() <= index {
+                        inputs.push_back(Value::constant(0));
+                    }
+                    inputs[index] = cell;
+                }
+            }
+        }
+        let mut machine = Machine {
+            memory,
+            idx: self.program.idx(),
+            relative_base: self.program.relative_base(),
+            inputs,
+            trace: Trace::default(),
+            stop_at: match goal {
+                Goal::Reach(address) => Some(address),
+                _ => None,
+            },
+        };
+        machine.run(self.budget);
+        machine.trace.memory = machine.memory;
+        machine.trace
+    }
+}
+
+impl Machine {
+    fn run(&mut self, budget: usize) {
+        for _ in 0..budget {
+            self.trace.executed.insert(self.idx);
+            if self.stop_at == Some(self.idx) || self.execute().is_none() {
+                return;
+            }
+        }
+    }
+
+    // Returns None when the machine stops
+    fn execute(&mut self) -> Option<()> {
+        let cell = self.load(self.idx);
+        let instruction = self.concretize(&cell);
+        let modes = [
+            instruction / 100 % 10,
+            instruction / 1000 % 10,
+            instruction / 10000 % 10,
+        ];
+        match instruction % 100 {
+            opcode @ 1 | opcode @ 2 => {
+                let a = self.param(1, modes[0])?;
+                let b = self.param(2, modes[1])?;
+                let dst = self.address(3, modes[2])?;
+                let result = if opcode == 1 {
+                    Value {
+                        concrete: a.concrete.checked_add(b.concrete)?,
+                        symbolic: a
+                            .symbolic
+                            .as_ref()
+                            .zip(b.symbolic.as_ref())
+                            .and_then(|(a, b)| a.add(b)),
+                    }
+                } else {
+                    Value {
+                        concrete: a.concrete.checked_mul(b.concrete)?,
+                        symbolic: multiply(&a, &b),
+                    }
+                };
+                self.memory.insert(dst, result);
+                self.idx += 4;
+            }
+            3 => {
+                let dst = self.address(1, modes[0])?;
+                let input = self.inputs.pop_front()?;
+                self.memory.insert(dst, input);
+                self.idx += 2;
+            }
+            4 => {
+                let value = self.param(1, modes[0])?;
+                self.trace.outputs.push(value);
+                self.idx += 2;
+            }
+            opcode @ 5 | opcode @ 6 => {
+                let condition = self.param(1, modes[0])?;
+                let jump = (condition.concrete != 0) == (opcode == 5);
+                let relation = if condition.concrete != 0 {
+                    Relation::Ne
+                } else {
+                    Relation::Eq
+                };
+                self.constrain(&condition, relation);
+                // as in the vm, the target is only read when the jump is taken
+                if jump {
+                    let target = self.param(2, modes[1])?;
+                    self.idx = to_address(self.concretize(&target))?;
+                } else {
+                    self.idx += 3;
+                }
+            }
+            opcode @ 7 | opcode @ 8 => {
+                let a = self.param(1, modes[0])?;
+                let b = self.param(2, modes[1])?;
+                let dst = self.address(3, modes[2])?;
+                let difference = Value {
+                    concrete: a.concrete.checked_sub(b.concrete)?,
+                    symbolic: a
+                        .symbolic
+                        .as_ref()
+                        .zip(b.symbolic.as_ref())
+                        .and_then(|(a, b)| a.sub(b)),
+                };
+                let (result, relation) = match (opcode, difference.concrete) {
+                    (7, d) if d < 0 => (1, Relation::Lt),
+                    (7, _) => (0, Relation::Ge),
+                    (_, 0) => (1, Relation::Eq),
+                    _ => (0, Relation::Ne),
+                };
+                // the result is fixed by the path constraint
+                self.constrain(&difference, relation);
+                self.memory.insert(dst, Value::constant(result));
+                self.idx += 4;
+            }
+            9 => {
+                let offset = self.param(1, modes[0])?;
+                let offset = self.concretize(&offset);
+                self.relative_base = self.relative_base.checked_add(offset)?;
+                self.idx += 2;
+            }
+            99 => {
+                self.trace.halted = true;
+                return None;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn load(&self, address: usize) -> Value {
+        self.memory
+            .get(&address)
+            .cloned()
+            .unwrap_or_else(|| Value::constant(0))
+    }
+
+    fn param(&mut self, offset: usize, mode: isize) -> Option<Value> {
+        let raw = self.load(self.idx + offset);
+        let address = match mode {
+            0 => raw.clone(),
+            1 => return Some(raw),
+            2 => Value {
+                concrete: raw.concrete.checked_add(self.relative_base)?,
+                symbolic: raw
+                    .symbolic
+                    .as_ref()
+                    .and_then(|s| s.add(&Linear::constant(self.relative_base))),
+            },
+            _ => return None,
+        };
+        let value = self.load(to_address(address.concrete)?);
+        if address.expression().is_some() || address.symbolic.is_none() {
+            // the cell depends on the unknowns : its content can't be expressed
+            Some(Value::opaque(value.concrete))
+        } else {
+            Some(value)
+        }
+    }
+
+    fn address(&mut self, offset: usize, mode: isize) -> Option<usize> {
+        let raw = self.load(self.idx + offset);
+        let raw = self.concretize(&raw);
+        match mode {
+            0 => to_address(raw),
+            1 => Some(self.idx + offset),
+            2 => to_address(raw.checked_add(self.relative_base)?),
+            _ => None,
+        }
+    }
+
+    // Fixes a value to its concrete value for the rest of the path
+    fn concretize(&mut self, value: &Value) -> isize {
+        self.constrain(value, Relation::Eq);
+        value.concrete
+    }
+
+    // Records the relation of the value with 0 in the path, equality being with its concrete value
+    fn constrain(&mut self, value: &Value, relation: Relation) {
+        match (&value.symbolic, relation) {
+            // not linear : left out of the path, solutions are checked by running them anyway
+            (None, _) => (),
+            (Some(expr), _) if expr.is_constant() => (),
+            (Some(expr), Relation::Eq) => {
+                if let Some(expr) = expr.sub(&Linear::constant(value.concrete)) {
+                    self.trace.path.push(Constraint { expr, relation });
+                }
+            }
+            (Some(expr), _) => self.trace.path.push(Constraint {
+                expr: expr.clone(),
+                relation,
+            }),
+        }
+    }
+}
+
+fn multiply(a: &Value, b: &Value) -> Option<Linear> {
+    match (a.symbolic.as_ref()?, b.symbolic.as_ref()?) {
+        (a, b) if a.is_constant() => b.scale(a.constant),
+        (a, b) if b.is_constant() => a.scale(b.constant),
+        _ => None,
+    }
+}
+
+fn to_address(value: isize) -> Option<usize> {
+    if value < 0 {
+        None
+    } else {
+        Some(value as usize)
+    }
+}
+
+fn satisfies(trace: &Trace, goal: Goal) -> bool {
+    match goal {
+        Goal::Output { index, value } => {
+            trace.outputs.get(index).map(|v| v.concrete) == Some(value)
+        }
+        Goal::Memory { address, value } => {
+            trace.halted && trace.memory.get(&address).map(|v| v.concrete).unwrap_or(0) == value
+        }
+        Goal::Reach(address) => trace.executed.contains(&address),
+    }
+}
+
+fn goal_constraint(trace: &Trace, goal: Goal) -> Option<Constraint> {
+    let (cell, value) = match goal {
+        Goal::Output { index, value } => (trace.outputs.get(index)?, value),
+        Goal::Memory { address, value } if trace.halted => (trace.memory.get(&address)?, value),
+        _ => return None,
+    };
+    Some(Constraint {
+        expr: cell.expression()?.sub(&Linear::constant(value))?,
+        relation: Relation::Eq,
+    })
+}
+
+// Finds values in the variable domains satisfying all the constraints. Backtracking only narrows
+// the domains with the constraints left with a single unknown : it gives up with None after
+// MAX_SEARCH_NODES values, so a wide domain can be missed but never hangs the solver
+pub fn solve_constraints(constraints: &[Constraint], variables: &[Variable]) -> Option<Vec<isize>> {
+    let mut assignment = vec![None; variables.len()];
+    let mut nodes = 0;
+    search(constraints, variables, &mut assignment, &mut nodes)
+}
+
+fn search(
+    constraints: &[Constraint],
+    variables: &[Variable],
+    assignment: &mut Vec<Option<isize>>,
+    nodes: &mut usize,
+) -> Option<Vec<isize>> {
+    *nodes += 1;
+    if *nodes > MAX_SEARCH_NODES {
+        return None;
+    }
+    let mut domains: Vec<(i128, i128)> = variables
+        .iter()
+        .map(|v| (*v.domain.start() as i128, *v.domain.end() as i128))
+        .collect();
+    for (variable, value) in assignment.iter().enumerate() {
+        if let Some(value) = value {
+            domains[variable] = (*value as i128, *value as i128);
+        }
+    }
+
+    for constraint in constraints {
+        let mut rest = constraint.expr.constant as i128;
+        let mut unassigned = Vec::new();
+        for (&variable, &coefficient) in &constraint.expr.terms {
+            match assignment[variable] {
+                Some(value) => rest += coefficient as i128 * value as i128,
+                None => unassigned.push((variable, coefficient as i128)),
+            }
+        }
+        match unassigned.as_slice() {
+            [] if !constraint.relation.holds(rest) => return None,
+            &[(variable, k)] => {
+                // k * x + rest relation 0
+                let (min, max) = &mut domains[variable];
+                match constraint.relation {
+                    Relation::Eq => {
+                        if rest % k != 0 {
+                            return None;
+                        }
+                        *min = (*min).max(-rest / k);
+                        *max = (*max).min(-rest / k);
+                    }
+                    Relation::Lt if k > 0 => *max = (*max).min(floor_div(-rest - 1, k)),
+                    Relation::Lt => *min = (*min).max(ceil_div(-rest - 1, k)),
+                    Relation::Ge if k > 0 => *min = (*min).max(ceil_div(-rest, k)),
+                    Relation::Ge => *max = (*max).min(floor_div(-rest, k)),
+                    Relation::Ne => (),
+                }
+            }
+            _ => (),
+        }
+    }
+    if domains.iter().any(|(min, max)| min > max) {
+        return None;
+    }
+
+    let next = (0..variables.len())
+        .filter(|&v| assignment[v].is_none())
+        .min_by_key(|&v| domains[v].1 - domains[v].0);
+    match next {
+        None => Some(assignment.iter().map(|v| v.unwrap()).collect()),
+        Some(variable) => {
+            for value in domains[variable].0..=domains[variable].1 {
+                assignment[variable] = Some(value as isize);
+                if let Some(solution) = search(constraints, variables, assignment, nodes) {
+                    return Some(solution);
+                }
+                if *nodes > MAX_SEARCH_NODES {
+                    break;
+                }
+            }
+            assignment[variable] = None;
+            None
+        }
+    }
+}
+
+fn floor_div(a: i128, b: i128) -> i128 {
+    let d = a / b;
+    if a % b != 0 && ((a < 0) != (b < 0)) {
+        d - 1
+    } else {
+        d
+    }
+}
+
+fn ceil_div(a: i128, b: i128) -> i128 {
+    -floor_div(-a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_linear_output() {
+        // output 3 * input + 7
+        let program = Program::new(vec![3, 20, 1002, 20, 3, 21, 1001, 21, 7, 21, 4, 21, 99]);
+        let solver = Solver::new(&program).unknown_input(0, -1000..=1000);
+        assert_eq!(
+            solver.solve(Goal::Output {
+                index: 0,
+                value: 100
+            }),
+            Some(vec![31])
+        );
+        assert_eq!(
+            solver.solve(Goal::Output {
+                index: 0,
+                value: 101
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn solve_through_equality_branch() {
+        // output input == 42
+        let program = Program::new(vec![3, 20, 1008, 20, 42, 21, 4, 21, 99]);
+        let solver = Solver::new(&program).unknown_input(0, 0..=100);
+        assert_eq!(
+            solver.solve(Goal::Output { index: 0, value: 1 }),
+            Some(vec![42])
+        );
+    }
+
+    #[test]
+    fn solve_to_reach_address() {
+        // jump to 12 if input < 10 : address 9 is reached only when input >= 10
+        let program = Program::new(vec![
+            3, 20, 1007, 20, 10, 21, 1005, 21, 12, 104, 0, 99, 104, 1, 99,
+        ]);
+        let solver = Solver::new(&program).unknown_input(0, 0..=100);
+        assert_eq!(solver.solve(Goal::Reach(9)), Some(vec![10]));
+        let trace = solver.trace(&[0], Goal::Reach(9));
+        assert_eq!(
+            trace.path,
+            vec![Constraint {
+                expr: Linear::variable(0).sub(&Linear::constant(10)).unwrap(),
+                relation: Relation::Lt,
+            }]
+        );
+    }
+
+    #[test]
+    fn solve_constraints_with_bounds() {
+        let variables = vec![
+            Variable {
+                unknown: Unknown::Input(0),
+                domain: 0..=99,
+            },
+            Variable {
+                unknown: Unknown::Input(1),
+                domain: 0..=99,
+            },
+        ];
+        // 100 * x + y - 7603 == 0
+        let mut expr = Linear::variable(0).scale(100).unwrap();
+        expr = expr.add(&Linear::variable(1)).unwrap();
+        expr.constant = -7603;
+        let constraints = vec![Constraint {
+            expr,
+            relation: Relation::Eq,
+        }];
+        assert_eq!(
+            solve_constraints(&constraints, &variables),
+            Some(vec![76, 3])
+        );
+    }
+
+    #[test]
+    fn solve_constraints_over_wide_domains() {
+        let variables: Vec<Variable> = (0..2)
+            .map(|input| Variable {
+                unknown: Unknown::Input(input),
+                domain: 0..=1_000_000_000,
+            })
+            .collect();
+        let difference = Linear::variable(0)
+            .sub(&Linear::variable(1))
+            .unwrap()
+            .scale(2)
+            .unwrap();
+        let constraint = |constant| Constraint {
+            expr: difference.add(&Linear::constant(constant)).unwrap(),
+            relation: Relation::Eq,
+        };
+        // 2 * (x - y) - 10 == 0
+        assert_eq!(
+            solve_constraints(&[constraint(-10)], &variables),
+            Some(vec![5, 0])
+        );
+        // 2 * (x - y) - 1 == 0 has no solution : the search gives up instead of trying every x
+        assert_eq!(solve_constraints(&[constraint(-1)], &variables), None);
+    }
+}
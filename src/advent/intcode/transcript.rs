@@ -0,0 +1,235 @@
+use std::fmt::Display;
+use std::fmt::Error;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+use crate::advent::intcode::Program;
+use crate::advent::intcode::ProgramState;
+
+// steps allowed after the last recorded event before a replay gives up
+const REPLAY_MARGIN: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Input,
+    Output,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub step: usize,
+    pub kind: EventKind,
+    pub value: isize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub index: usize,
+    pub expected: Option<Event>,
+    pub actual: Option<Event>,
+    // state of the replayed program, a BudgetExhausted one is always a divergence
+    pub state: ProgramState,
+}
+
+impl Transcript {
+    pub fn record(&mut self, step: usize, kind: EventKind, value: isize) {
+        self.events.push(Event { step, kind, value });
+    }
+
+    pub fn inputs(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(|e| e.kind == EventKind::Input)
+    }
+
+    pub fn outputs(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().filter(|e| e.kind == EventKind::Output)
+    }
+
+    pub fn load(file_name: &str) -> Result<Self, String> {
+        crate::read_file(file_name).parse()
+    }
+
+    pub fn save(&self, file_name: &str) -> std::io::Result<()> {
+        std::fs::write(file_name, self.to_string())
+    }
+
+    // Feeds the recorded inputs to the program and checks it produces the recorded outputs
+    pub fn replay(&self, program: Program) -> Result<(), Divergence> {
+        self.replay_with(program, |expected, actual| expected == actual)
+    }
+
+    // Same as replay, without checking the steps at which outputs are produced
+    pub fn replay_outputs(&self, program: Program) -> Result<(), Divergence> {
+        self.replay_with(program, |expected, actual| expected.value == actual.value)
+    }
+
+    // A regression may run forever : a replay can't take much longer than the recording
+    pub fn budget(&self) -> usize {
+        let recorded_steps = self.events.last().map_or(0, |e| e.step);
+        2 * recorded_steps + REPLAY_MARGIN
+    }
+
+    fn replay_with(
+        &self,
+        mut program: Program,
+        same: fn(&Event, &Event) -> bool,
+    ) -> Result<(), Divergence> {
+        program.input.extend(self.inputs().map(|e| e.value));
+        program.record_transcript();
+        program.execute_with_budget(self.budget());
+        let exhausted = matches!(program.state, ProgramState::BudgetExhausted { .. });
+        let actual = program.transcript.unwrap_or_default();
+
+        let mut expected_outputs = self.outputs();
+        let mut actual_outputs = actual.outputs();
+        let mut index = 0;
+        loop {
+            match (expected_outputs.next(), actual_outputs.next()) {
+                (None, None) if !exhausted => return Ok(()),
+                (Some(expected), Some(actual)) if same(expected, actual) => index += 1,
+                (expected, actual) => {
+                    return Err(Divergence {
+                        index,
+                        expected: expected.cloned(),
+                        actual: actual.cloned(),
+                        state: program.state,
+                    })
+                }
+            }
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let kind = match self.kind {
+            EventKind::Input => "in",
+            EventKind::Output => "out",
+        };
+        write!(f, "{} {} {}", self.step, kind, self.value)
+    }
+}
+
+impl FromStr for Event {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() != 3 {
+            return Err(format!("bad transcript line : {}", line));
+        }
+        let kind = match parts[1] {
+            "in" => EventKind::Input,
+            "out" => EventKind::Output,
+            _ => return Err(format!("bad event kind : {}", parts[1])),
+        };
+        Ok(Event {
+            step: parts[0]
+                .parse()
+                .map_err(|_| format!("bad step : {}", parts[0]))?,
+            kind,
+            value: parts[2]
+                .parse()
+                .map_err(|_| format!("bad value : {}", parts[2]))?,
+        })
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = String;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        Ok(Transcript {
+            events: content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.parse())
+                .collect::<Result<Vec<Event>, String>>()?,
+        })
+    }
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let describe = |event: Option<Event>| match event {
+            Some(e) => format!("{} at step {}", e.value, e.step),
+            None => "nothing".to_string(),
+        };
+        write!(
+            f,
+            "output #{} differs : expected {}, got {}",
+            self.index,
+            describe(self.expected),
+            describe(self.actual)
+        )?;
+        if let ProgramState::BudgetExhausted { .. } = self.state {
+            write!(f, " ({})", self.state)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::intcode::parse_input;
+
+    #[test]
+    fn record_and_replay_day09() {
+        let mut program = Program::new(parse_input("09"));
+        program.record_transcript();
+        program.input.push_back(1);
+        program.execute();
+        let transcript: Transcript = program.transcript.unwrap().to_string().parse().unwrap();
+        assert_eq!(transcript.inputs().count(), 1);
+        assert_eq!(
+            transcript.outputs().last().map(|e| e.value),
+            Some(4261108180)
+        );
+        assert_eq!(transcript.replay(Program::new(parse_input("09"))), Ok(()));
+    }
+
+    #[test]
+    fn replay_reports_first_divergent_output() {
+        let transcript: Transcript = "0 in 8\n4 out 1\n".parse().unwrap();
+        let program = Program::new(vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 7]);
+        let divergence = transcript.replay(program).unwrap_err();
+        assert_eq!(divergence.index, 0);
+        assert_eq!(
+            divergence.to_string(),
+            "output #0 differs : expected 1 at step 4, got 0 at step 2"
+        );
+    }
+
+    #[test]
+    fn replay_gives_up_on_endless_programs() {
+        let transcript: Transcript = "0 in 8\n1 out 8\n".parse().unwrap();
+        // outputs its input then loops forever
+        let program = Program::new(vec![3, 7, 4, 7, 1105, 1, 4, 0]);
+        let divergence = transcript.replay(program).unwrap_err();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(
+            divergence.to_string(),
+            "output #1 differs : expected nothing, got nothing (step budget exhausted at 4)"
+        );
+    }
+
+    #[test]
+    fn bad_lines_are_rejected() {
+        assert!("0 foo 1".parse::<Transcript>().is_err());
+        assert!("a in 1".parse::<Transcript>().is_err());
+    }
+}
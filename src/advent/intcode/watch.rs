@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use crate::advent::intcode::Program;
+use crate::advent::intcode::ProgramState;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Change {
+    pub address: usize,
+    pub old: isize,
+    pub new: isize,
+}
+
+// Changes on contiguous addresses
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub start: usize,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub step: usize,
+    pub address: usize,
+    pub old: isize,
+    pub new: isize,
+}
+
+pub struct Watch {
+    values: BTreeMap<usize, isize>,
+    pub timeline: Vec<WatchEvent>,
+}
+
+impl Region {
+    pub fn end(&self) -> usize {
+        self.start + self.changes.len()
+    }
+}
+
+// Unloaded cells are read as 0, like the VM does
+pub fn diff(before: &BTreeMap<usize, isize>, after: &BTreeMap<usize, isize>) -> Vec<Region> {
+    let addresses: BTreeSet<usize> = before.keys().chain(after.keys()).cloned().collect();
+    let mut regions: Vec<Region> = Vec::new();
+    for address in addresses {
+        let old = *before.get(&address).unwrap_or(&0);
+        let new = *after.get(&address).unwrap_or(&0);
+        if old == new {
+            continue;
+        }
+        let change = Change { address, old, new };
+        match regions.last_mut() {
+            Some(region) if region.end() == address => region.changes.push(change),
+            _ => regions.push(Region {
+                start: address,
+                changes: vec![change],
+            }),
+        }
+    }
+    regions
+}
+
+pub fn diff_programs(before: &Program, after: &Program) -> Vec<Region> {
+    diff(&before.snapshot(), &after.snapshot())
+}
+
+impl Watch {
+    pub fn new(program: &Program, addresses: &[usize]) -> Self {
+        Watch {
+            values: addresses
+                .iter()
+                .map(|&address| (address, program.peek(address)))
+                .collect(),
+            timeline: Vec::new(),
+        }
+    }
+
+    // Same as Program::execute, recording every change of the watched cells
+    pub fn execute(&mut self, program: &mut Program) {
+        loop {
+            program.execute_with_budget(1);
+            for (&address, value) in self.values.iter_mut() {
+                let new = program.peek(address);
+                if new != *value {
+                    self.timeline.push(WatchEvent {
+                        step: program.steps(),
+                        address,
+                        old: *value,
+                        new,
+                    });
+                    *value = new;
+                }
+            }
+            if !matches!(program.state, ProgramState::BudgetExhausted { .. }) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_groups_contiguous_changes() {
+        let mut program = Program::new(vec![1101, 1, 2, 30, 1101, 3, 4, 31, 1101, 5, 6, 40, 99]);
+        let before = program.clone();
+        program.execute();
+        let regions = diff_programs(&before, &program);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].start, 30);
+        assert_eq!(regions[0].end(), 32);
+        assert_eq!(
+            regions[1].changes,
+            vec![Change {
+                address: 40,
+                old: 0,
+                new: 11
+            }]
+        );
+    }
+
+    #[test]
+    fn watch_builds_a_timeline() {
+        // counts down from 3 in cell 20
+        let mut program = Program::new(vec![1101, 3, 0, 20, 1001, 20, -1, 20, 1005, 20, 4, 99]);
+        let mut watch = Watch::new(&program, &[20]);
+        watch.execute(&mut program);
+        assert!(program.state.is_halted());
+        assert_eq!(
+            watch
+                .timeline
+                .iter()
+                .map(|e| (e.step, e.new))
+                .collect::<Vec<(usize, isize)>>(),
+            vec![(1, 3), (2, 2), (4, 1), (6, 0)]
+        );
+    }
+}
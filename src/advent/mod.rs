@@ -25,3 +25,4 @@ pub mod day24;
 pub mod day25;
 pub mod geometry;
 pub mod intcode;
+pub mod search;
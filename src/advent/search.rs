@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+use std::collections::vec_deque::VecDeque;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::advent::geometry::Plane;
+use crate::advent::geometry::Point;
+
+// Outcome of a search from a start state. When it stops on a goal, only the distances of the
+// states settled before it are final
+#[derive(Clone, Debug)]
+pub struct Search<S> {
+    pub start: S,
+    pub goal: Option<S>,
+    pub distances: HashMap<S, usize>,
+    pub predecessors: HashMap<S, S>,
+}
+
+impl<S> Search<S>
+where
+    S: Clone + Eq + Hash,
+{
+    fn new(start: S) -> Self {
+        let mut distances = HashMap::new();
+        distances.insert(start.clone(), 0);
+        Search {
+            start,
+            goal: None,
+            distances,
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.distances.get(state).cloned()
+    }
+
+    // Distance of the goal the search stopped on
+    pub fn goal_distance(&self) -> Option<usize> {
+        self.goal.as_ref().and_then(|goal| self.distance(goal))
+    }
+
+    // States from the start to the target, both included
+    pub fn path(&self, target: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(target) {
+            return None;
+        }
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Breadth first : every move costs 1. Stops on the first goal, is_goal can always be false to
+// explore every reachable state
+pub fn bfs<S>(
+    start: S,
+    successors: &dyn Fn(&S) -> Vec<S>,
+    is_goal: &dyn Fn(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&current) {
+            search.goal = Some(current);
+            break;
+        }
+        let distance = search.distances[&current];
+        for next in successors(&current) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance + 1);
+                search.predecessors.insert(next.clone(), current.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    search
+}
+
+// Weighted moves, successors return the next states with the cost to reach them
+pub fn dijkstra<S>(
+    start: S,
+    successors: &dyn Fn(&S) -> Vec<(S, usize)>,
+    is_goal: &dyn Fn(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+{
+    astar(start, successors, &|_| 0, is_goal)
+}
+
+// Dijkstra guided by a heuristic, which must never overestimate the remaining cost to a goal
+pub fn astar<S>(
+    start: S,
+    successors: &dyn Fn(&S) -> Vec<(S, usize)>,
+    heuristic: &dyn Fn(&S) -> usize,
+    is_goal: &dyn Fn(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+{
+    let mut search = Search::new(start.clone());
+    // states are pushed by index so they don't need to be ordered
+    let mut states = vec![start.clone()];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), 0, 0)));
+
+    while let Some(Reverse((_, distance, idx))) = heap.pop() {
+        let current = states[idx].clone();
+        if distance > search.distances[&current] {
+            // already settled with a shorter distance
+            continue;
+        }
+        if is_goal(&current) {
+            search.goal = Some(current);
+            break;
+        }
+        for (next, cost) in successors(&current) {
+            let next_distance = distance + cost;
+            if !matches!(search.distances.get(&next), Some(&known) if known <= next_distance) {
+                search.distances.insert(next.clone(), next_distance);
+                search.predecessors.insert(next.clone(), current.clone());
+                heap.push(Reverse((
+                    next_distance + heuristic(&next),
+                    next_distance,
+                    states.len(),
+                )));
+                states.push(next);
+            }
+        }
+    }
+    search
+}
+
+// Successors on a plane : the present neighbours that can be walked on
+pub fn plane_successors<'a, T, P>(
+    plane: &'a P,
+    passable: &'a dyn Fn(&T) -> bool,
+) -> impl Fn(&Point) -> Vec<Point> + 'a
+where
+    P: Plane<T>,
+{
+    move |point| {
+        plane
+            .neighbours(point)
+            .into_iter()
+            .filter(|(_, value)| passable(value))
+            .map(|(next, _)| next)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advent::geometry::Map;
+
+    const MAZE: &str = "\
+#########
+#S..#...#
+#.#.#.#.#
+#.#...#E#
+#########";
+
+    #[test]
+    fn bfs_on_map() {
+        let map: Map<char> = MAZE.parse().unwrap();
+        let start = map.find('S').unwrap();
+        let end = map.find('E').unwrap();
+        let successors = plane_successors(&map, &|&c| c != '#');
+
+        let search = bfs(start, &successors, &|&p| p == end);
+        assert_eq!(search.goal, Some(end));
+        assert_eq!(search.goal_distance(), Some(12));
+        let path = search.path(&end).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), (start, end));
+        assert!(path
+            .windows(2)
+            .all(|step| step[0].manhattan_distance_from(&step[1]) == 1));
+
+        let everything = bfs(start, &successors, &|_| false);
+        assert_eq!(everything.goal, None);
+        assert_eq!(everything.distances.len(), 15);
+        assert_eq!(everything.distance(&Point::new(1, 3)), Some(2));
+        assert_eq!(everything.path(&Point::new(0, 0)), None);
+    }
+
+    #[test]
+    fn weighted_searches() {
+        // from n, go to n + 1 for 1 or to n * 2 for 2
+        let successors = |&n: &usize| vec![(n + 1, 1), (n * 2, 2)];
+        let search = dijkstra(1, &successors, &|&n| n == 24);
+        assert_eq!(search.goal_distance(), Some(8));
+        assert_eq!(search.path(&24), Some(vec![1, 2, 3, 6, 12, 24]));
+
+        let guided = astar(1, &successors, &|&n| if n < 24 { 1 } else { 0 }, &|&n| {
+            n == 24
+        });
+        assert_eq!(guided.goal_distance(), Some(8));
+        assert!(guided.distances.len() <= search.distances.len());
+    }
+}
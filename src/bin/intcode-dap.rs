@@ -0,0 +1,13 @@
+use std::io;
+
+use aoc_2019::advent::intcode::dap;
+
+// Debug adapter for Intcode programs, speaking the protocol on stdin / stdout
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(error) = dap::serve(stdin.lock(), stdout.lock()) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
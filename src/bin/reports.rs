@@ -0,0 +1,21 @@
+use aoc_2019::advent::day08;
+use aoc_2019::advent::day11;
+use aoc_2019::advent::day15;
+use aoc_2019::advent::geometry::export;
+use aoc_2019::advent::intcode::parse_input;
+use aoc_2019::advent::intcode::Program;
+
+// Saves the pictures of the days in the reports directory, run from the root of the crate
+fn main() {
+    let maze = day15::explore(Program::new(parse_input("15"))).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    });
+    for result in [day08::report(), day11::report(), day15::report(&maze)] {
+        if let Err(error) = result {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    }
+    println!("pictures saved in {}", export::REPORTS);
+}